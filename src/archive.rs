@@ -0,0 +1,172 @@
+use crate::diff::print_origin;
+use console::style;
+use rustlings::exercise::Exercise;
+use rustlings::hints::RevealedHints;
+use rustlings::pristine::{back_up, pristine_source};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Bumped whenever archives change in a way older versions can't read
+const ARCHIVE_VERSION: u32 = 1;
+
+// The learner's work, to carry it over to another machine
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    version: u32,
+    // The SHA-256 of the original version of every exercise, by name, to
+    // notice exercises that changed upstream in the meantime
+    manifest: BTreeMap<String, String>,
+    // The exercises the learner changed
+    files: Vec<SavedFile>,
+    hints: RevealedHints,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedFile {
+    exercise: String,
+    path: PathBuf,
+    // The SHA-256 of the contents, to notice damaged archives
+    sha256: String,
+    contents: String,
+}
+
+fn sha256(data: &str) -> String {
+    format!("{:x}", Sha256::digest(data.as_bytes()))
+}
+
+// Write every exercise that differs from its original version to the archive
+// at `path`, along with the revealed hints
+pub fn save(path: &Path, exercises: &[Exercise]) -> Result<(), String> {
+    let mut archive = Archive {
+        version: ARCHIVE_VERSION,
+        manifest: BTreeMap::new(),
+        files: Vec::new(),
+        hints: RevealedHints::load(),
+    };
+    for exercise in exercises {
+        // The manifest holds the original the course ships, which is the same
+        // on every machine however much of their work the learner committed,
+        // so that `restore` notices when the course updated the exercise
+        // since. Exercises without an original are always saved.
+        let original = pristine_source(exercise).ok();
+        if let Some(original) = &original {
+            archive
+                .manifest
+                .insert(exercise.name.clone(), sha256(original));
+        }
+        let contents = fs::read_to_string(&exercise.path)
+            .map_err(|e| format!("Could not read {exercise}: {e}"))?;
+        if original.as_ref() != Some(&contents) {
+            archive.files.push(SavedFile {
+                exercise: exercise.name.clone(),
+                path: exercise.path.clone(),
+                sha256: sha256(&contents),
+                contents,
+            });
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+    success!("Saved your work to {}", path.display());
+    println!(
+        "{} changed exercise(s) of {} were saved.",
+        archive.files.len(),
+        exercises.len()
+    );
+    print_origin();
+    Ok(())
+}
+
+// Put the exercises from the archive at `path` back in place and merge the
+// revealed hints. Exercises that changed upstream are left alone unless
+// `force` is given. Returns whether everything was restored.
+pub fn restore(path: &Path, exercises: &[Exercise], force: bool) -> Result<bool, String> {
+    let json =
+        fs::read_to_string(path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+    let archive: Archive = serde_json::from_str(&json)
+        .map_err(|e| format!("{} is not a rustlings archive: {e}", path.display()))?;
+    if archive.version != ARCHIVE_VERSION {
+        return Err(format!(
+            "{} was saved by another version of rustlings",
+            path.display()
+        ));
+    }
+    if let Some(damaged) = archive
+        .files
+        .iter()
+        .find(|f| sha256(&f.contents) != f.sha256)
+    {
+        return Err(format!(
+            "{} is damaged, {} doesn't match its checksum",
+            path.display(),
+            damaged.path.display()
+        ));
+    }
+
+    let mut restored = 0;
+    let mut replaced = 0;
+    let mut conflicts = 0;
+    for file in &archive.files {
+        let Some(exercise) = exercises.iter().find(|e| e.name == file.exercise) else {
+            println!(
+                "{} {} is no longer part of the course, skipped it",
+                style("conflict:").red(),
+                file.path.display()
+            );
+            conflicts += 1;
+            continue;
+        };
+        // The learner worked on another original version of the exercise
+        let original = pristine_source(exercise).ok();
+        if original.as_deref().map(sha256).as_ref() != archive.manifest.get(&file.exercise) {
+            if !force {
+                println!(
+                    "{} {} changed upstream since your work was saved, kept the current version",
+                    style("conflict:").red(),
+                    exercise
+                );
+                conflicts += 1;
+                continue;
+            }
+            println!(
+                "{} {} changed upstream since your work was saved, restored it anyway",
+                style("warning:").yellow(),
+                exercise
+            );
+        }
+        let current = fs::read_to_string(&exercise.path).ok();
+        if current.as_deref() == Some(file.contents.as_str()) {
+            restored += 1;
+            continue;
+        }
+        if current.is_some() && current != original {
+            back_up(exercise).map_err(|e| format!("Could not back up {exercise}: {e}"))?;
+            replaced += 1;
+        }
+        fs::write(&exercise.path, &file.contents)
+            .map_err(|e| format!("Could not write {exercise}: {e}"))?;
+        restored += 1;
+    }
+
+    let mut hints = RevealedHints::load();
+    hints.merge(&archive.hints);
+    if hints.save().is_err() {
+        warn!("Could not record the hints revealed in {}", path.display());
+    }
+
+    success!("Restored {} exercise(s)", restored);
+    if replaced > 0 {
+        println!(
+            "{replaced} of your current version(s) were replaced, \
+             `rustlings reset --undo <name>` brings one back."
+        );
+    }
+    if conflicts > 0 {
+        println!("{conflicts} exercise(s) were not restored, `--force` restores them anyway.");
+    }
+    Ok(conflicts == 0)
+}
@@ -0,0 +1,259 @@
+use crate::grade_course;
+use console::style;
+use rustlings::exercise::{normalize, Exercise};
+use rustlings::grade::ExerciseCheckList;
+use rustlings::hints::RevealedHints;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process;
+
+// The layout of a submission bundle
+const SOURCES_DIR: &str = "sources";
+const RESULTS_FILE: &str = "check_result.json";
+const HINTS_FILE: &str = "hints.json";
+const MANIFEST_FILE: &str = "manifest.json";
+
+// The SHA-256 of every other file in a bundle
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<PathBuf, String>,
+}
+
+fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+// Whether the path stays inside the directory it is joined to
+fn is_contained(path: &Path) -> bool {
+    path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+// The path of a file of the course relative to `course`, the directory of
+// the info file, so that bundles don't depend on how `--info` was given
+fn course_path(file: &Path, course: &Path) -> Result<PathBuf, String> {
+    normalize(file)
+        .strip_prefix(normalize(course))
+        .ok()
+        .filter(|relative| is_contained(relative))
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            format!(
+                "{} is outside the course directory, so it can't be bundled",
+                file.display()
+            )
+        })
+}
+
+// Where the source of an exercise is kept inside a bundle
+fn source_path(exercise: &Exercise, course: &Path) -> Result<PathBuf, String> {
+    Ok(Path::new(SOURCES_DIR).join(course_path(&exercise.path, course)?))
+}
+
+// Writes the files of a bundle and records them in its manifest
+struct BundleWriter<'a> {
+    dir: &'a Path,
+    manifest: Manifest,
+}
+
+impl BundleWriter<'_> {
+    fn write(&mut self, file: PathBuf, data: &[u8]) -> Result<(), String> {
+        let path = self.dir.join(&file);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Could not create {}: {e}", dir.display()))?;
+        }
+        fs::write(&path, data).map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+        self.manifest.files.insert(file, sha256(data));
+        Ok(())
+    }
+
+    fn write_json(&mut self, file: &str, value: &impl Serialize) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+        self.write(PathBuf::from(file), json.as_bytes())
+    }
+}
+
+// Grade the exercises of the course in `course` like `cicvverify` and write
+// the results to the bundle directory `out`, along with the sources they were
+// graded from, the revealed hints and a manifest of their hashes
+pub async fn submit(
+    out: &Path,
+    course: &Path,
+    exercises: Vec<Exercise>,
+    hint_penalty: f32,
+) -> Result<(), String> {
+    if fs::read_dir(out).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!("{} already exists and isn't empty", out.display()));
+    }
+    let revealed_hints = RevealedHints::load();
+    let sources = exercises
+        .iter()
+        .map(|exercise| {
+            let path = source_path(exercise, course)?;
+            fs::read(&exercise.path)
+                .map(|source| (path, source))
+                .map_err(|e| format!("Could not read {exercise}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let check_list = grade_course(exercises, &revealed_hints, hint_penalty).await;
+
+    let mut writer = BundleWriter {
+        dir: out,
+        manifest: Manifest {
+            files: BTreeMap::new(),
+        },
+    };
+    for (path, source) in sources {
+        writer.write(path, &source)?;
+    }
+    writer.write_json(RESULTS_FILE, &check_list)?;
+    writer.write_json(HINTS_FILE, &revealed_hints)?;
+    let manifest = serde_json::to_string_pretty(&writer.manifest).map_err(|e| e.to_string())?;
+    fs::write(out.join(MANIFEST_FILE), manifest)
+        .map_err(|e| format!("Could not write the manifest: {e}"))?;
+
+    success!("Wrote your submission to {}", out.display());
+    println!(
+        "{} of {} exercises passed.",
+        check_list.statistics.total_succeeds, check_list.statistics.total_exercations
+    );
+    Ok(())
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let json =
+        fs::read_to_string(path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+    serde_json::from_str(&json).map_err(|e| format!("{} is invalid: {e}", path.display()))
+}
+
+// Copy the submitted sources into a scratch copy of the course at `dir`.
+// Build scripts come from the course itself, not from the submission.
+// A scratch copy of the course, removed when it goes out of scope, even
+// when grading panics
+struct Scratch(PathBuf);
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+// Every exercise keeps its place relative to the course directory `course`.
+fn prepare_course(
+    dir: &Path,
+    bundle: &Path,
+    course: &Path,
+    exercises: &[Exercise],
+) -> Result<(), String> {
+    let _ = fs::remove_dir_all(dir);
+    for exercise in exercises {
+        let target = dir.join(course_path(&exercise.path, course)?);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Could not create {}: {e}", parent.display()))?;
+        }
+        // A missing source is graded as a failure like any other
+        let _ = fs::copy(bundle.join(source_path(exercise, course)?), &target);
+        let extra_files = exercise
+            .runner()
+            .map(|runner| runner.extra_files(exercise))
+            .unwrap_or_default();
+        for file in extra_files.iter().filter(|file| file.exists()) {
+            fs::copy(file, dir.join(course_path(file, course)?))
+                .map_err(|e| format!("Could not copy {}: {e}", file.display()))?;
+        }
+    }
+    Ok(())
+}
+
+// Check the manifest of the bundle, grade its sources again in a scratch copy
+// of the course in `course` and compare the results with the submitted ones. The penalty
+// for revealed hints is the instructor's, whatever the bundle was graded with.
+// Every difference is printed, and `false` is returned if there were any.
+pub async fn grade_bundle(
+    bundle: &Path,
+    course: &Path,
+    exercises: &[Exercise],
+    hint_penalty: f32,
+) -> Result<bool, String> {
+    let manifest: Manifest = read_json(&bundle.join(MANIFEST_FILE))?;
+    let mut problems = Vec::new();
+    for (file, hash) in &manifest.files {
+        if !is_contained(file) {
+            problems.push(format!("{} points outside the bundle", file.display()));
+            continue;
+        }
+        match fs::read(bundle.join(file)) {
+            Ok(data) if sha256(&data) == *hash => {}
+            Ok(_) => problems.push(format!(
+                "{} was changed after it was submitted",
+                file.display()
+            )),
+            Err(_) => problems.push(format!("{} is missing", file.display())),
+        }
+    }
+    for exercise in exercises {
+        if !manifest.files.contains_key(&source_path(exercise, course)?) {
+            problems.push(format!("the source of {} wasn't submitted", exercise.name));
+        }
+    }
+    let claimed: ExerciseCheckList = read_json(&bundle.join(RESULTS_FILE))?;
+    let revealed_hints: RevealedHints = read_json(&bundle.join(HINTS_FILE))?;
+
+    // Grading happens in a scratch copy of the course, with the exercises
+    // pointing at their copies there
+    let scratch = Scratch(env::temp_dir().join(format!("rustlings-grade-{}", process::id())));
+    prepare_course(&scratch.0, bundle, course, exercises)?;
+    let copies = exercises
+        .iter()
+        .map(|exercise| {
+            Ok(Exercise {
+                path: scratch.0.join(course_path(&exercise.path, course)?),
+                ..exercise.clone()
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    let regraded = grade_course(copies, &revealed_hints, hint_penalty).await;
+    drop(scratch);
+
+    for result in &regraded.exercises {
+        let Some(claim) = claimed.exercises.iter().find(|c| c.name == result.name) else {
+            problems.push(format!("there is no submitted result for {}", result.name));
+            continue;
+        };
+        if claim.result != result.result || (claim.score - result.score).abs() > 1e-4 {
+            problems.push(format!(
+                "{} was submitted as {} with a score of {}, but {} with a score of {} now",
+                result.name,
+                if claim.result { "passing" } else { "failing" },
+                claim.score,
+                if result.result { "passes" } else { "fails" },
+                result.score
+            ));
+        }
+    }
+    for claim in &claimed.exercises {
+        if !regraded.exercises.iter().any(|r| r.name == claim.name) {
+            problems.push(format!("{} isn't an exercise of this course", claim.name));
+        }
+    }
+
+    println!();
+    for problem in &problems {
+        println!("{} {problem}", style("mismatch:").red());
+    }
+    if problems.is_empty() {
+        success!(
+            "The bundle matches: {} exercises passed",
+            regraded.statistics.total_succeeds
+        );
+    } else {
+        warn!("Found {} mismatch(es) in the bundle", problems.len());
+    }
+    Ok(problems.is_empty())
+}
@@ -0,0 +1,461 @@
+use crate::solution::{has_solutions, solution_path, solved};
+use console::style;
+use glob::{glob, Pattern};
+use regex::Regex;
+use rustlings::exercise::{
+    normalize, Difficulty, Edition, Exercise, ExerciseList, Profile, I_AM_DONE_REGEX,
+};
+use rustlings::runner;
+use rustlings::{evaluate, Outcome};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+
+// The same layout as `ExerciseList`, but every field is optional and keeps
+// track of where it was found, so that problems can be reported with a line
+// number instead of a deserialization error.
+#[derive(Deserialize)]
+struct RawList {
+    #[serde(default)]
+    include: Vec<Spanned<String>>,
+    #[serde(default)]
+    exercises: Vec<RawExercise>,
+    #[serde(default)]
+    profiles: BTreeMap<String, toml::Value>,
+    deny_warnings: Option<Spanned<toml::Value>>,
+}
+
+#[derive(Deserialize)]
+struct RawExercise {
+    name: Option<Spanned<String>>,
+    path: Option<Spanned<String>>,
+    mode: Option<Spanned<String>>,
+    command: Option<Spanned<String>>,
+    build: Option<Spanned<toml::Value>>,
+    min_documented: Option<Spanned<toml::Value>>,
+    deny_warnings: Option<Spanned<toml::Value>>,
+    hint: Option<Spanned<String>>,
+    hints: Option<Spanned<Vec<String>>>,
+    difficulty: Option<Spanned<toml::Value>>,
+    tags: Option<Spanned<toml::Value>>,
+    estimated_minutes: Option<Spanned<toml::Value>>,
+    edition: Option<Spanned<String>>,
+}
+
+// A place in one of the course's TOML files.
+// A line number of 0 stands for the file as a whole.
+#[derive(Clone)]
+struct Location {
+    file: PathBuf,
+    line: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.file.display())
+        } else {
+            write!(f, "{}:{}", self.file.display(), self.line)
+        }
+    }
+}
+
+struct Problem {
+    location: Location,
+    message: String,
+}
+
+#[derive(Default)]
+struct Checker {
+    problems: Vec<Problem>,
+    names: HashMap<String, Location>,
+    paths: HashSet<PathBuf>,
+    seen_files: HashSet<PathBuf>,
+    profiles: Vec<(String, Location, Profile)>,
+}
+
+impl Checker {
+    fn report(&mut self, location: Location, message: impl Into<String>) {
+        self.problems.push(Problem {
+            location,
+            message: message.into(),
+        });
+    }
+
+    // The value of `field` as a `T`, reporting it at `location` when it
+    // isn't `expected`
+    fn parse<T: DeserializeOwned>(
+        &mut self,
+        location: Location,
+        field: &str,
+        value: &toml::Value,
+        expected: &str,
+    ) -> Option<T> {
+        match value.clone().try_into() {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                self.report(
+                    location,
+                    format!("`{field}` must be {expected}, not {value}"),
+                );
+                None
+            }
+        }
+    }
+
+    fn check_file(&mut self, file: &Path, included_from: Option<Location>) {
+        let here = |line| Location {
+            file: file.to_path_buf(),
+            line,
+        };
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                let location = included_from.unwrap_or_else(|| here(0));
+                self.report(location, format!("cannot read {}: {e}", file.display()));
+                return;
+            }
+        };
+        if let Ok(canonical) = file.canonicalize() {
+            if !self.seen_files.insert(canonical) {
+                let location = included_from.unwrap_or_else(|| here(0));
+                self.report(
+                    location,
+                    format!("{} is included more than once", file.display()),
+                );
+                return;
+            }
+        }
+        let list: RawList = match toml::from_str(&source) {
+            Ok(list) => list,
+            Err(e) => {
+                let line = e.line_col().map_or(0, |(line, _)| line + 1);
+                self.report(here(line), format!("invalid TOML: {e}"));
+                return;
+            }
+        };
+
+        let base = file.parent().unwrap_or_else(|| Path::new(""));
+        let line_of = |offset: usize| source[..offset].matches('\n').count() + 1;
+        let headers: Vec<usize> = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.trim_start().starts_with("[[exercises]]"))
+            .map(|(i, _)| i + 1)
+            .collect();
+        // The exercises as their runners see them
+        let tables: Vec<toml::Value> = toml::from_str::<toml::Value>(&source)
+            .ok()
+            .and_then(|value| value.get("exercises")?.as_array().cloned())
+            .unwrap_or_default();
+
+        if let Some(deny_warnings) = &list.deny_warnings {
+            let location = here(line_of(deny_warnings.start()));
+            self.parse::<bool>(
+                location,
+                "deny_warnings",
+                deny_warnings.get_ref(),
+                "true or false",
+            );
+        }
+
+        for (i, exercise) in list.exercises.iter().enumerate() {
+            let header = here(headers.get(i).copied().unwrap_or(0));
+            let at = |field: &Spanned<String>| here(line_of(field.start()));
+            let value_at = |field: &Spanned<toml::Value>| here(line_of(field.start()));
+
+            match &exercise.name {
+                None => self.report(header.clone(), "exercise has no `name`"),
+                Some(name) => {
+                    if let Some(first) = self.names.get(name.get_ref()) {
+                        let message = format!(
+                            "duplicate exercise name '{}' (first defined at {first})",
+                            name.get_ref()
+                        );
+                        self.report(at(name), message);
+                    } else {
+                        self.names.insert(name.get_ref().clone(), at(name));
+                    }
+                }
+            }
+
+            match &exercise.path {
+                None => self.report(header.clone(), "exercise has no `path`"),
+                Some(path) => self.check_exercise_path(&base.join(path.get_ref()), at(path)),
+            }
+
+            match &exercise.mode {
+                None => self.report(header.clone(), "exercise has no `mode`"),
+                Some(mode) => match runner::get(mode.get_ref()) {
+                    Err(error) => self.report(at(mode), error.to_string()),
+                    // Which settings fit the mode is up to its runner
+                    Ok(runner) => {
+                        let parsed = tables.get(i).cloned().map(toml::Value::try_into);
+                        let invalid = match parsed {
+                            Some(Ok(parsed)) => runner.validate(&parsed),
+                            _ => Vec::new(),
+                        };
+                        for invalid in invalid {
+                            let location = match invalid.field {
+                                "command" => exercise.command.as_ref().map(at),
+                                "build" => exercise.build.as_ref().map(value_at),
+                                "min_documented" => exercise.min_documented.as_ref().map(value_at),
+                                "deny_warnings" => exercise.deny_warnings.as_ref().map(value_at),
+                                _ => None,
+                            };
+                            self.report(location.unwrap_or_else(|| at(mode)), invalid.message);
+                        }
+                    }
+                },
+            }
+
+            if let Some(min_documented) = &exercise.min_documented {
+                let location = value_at(min_documented);
+                let value = min_documented.get_ref();
+                self.parse::<usize>(location, "min_documented", value, "a whole number");
+            }
+
+            if let Some(build) = &exercise.build {
+                let location = value_at(build);
+                self.parse::<String>(location, "build", build.get_ref(), "a command");
+            }
+
+            if let Some(deny_warnings) = &exercise.deny_warnings {
+                let location = value_at(deny_warnings);
+                let value = deny_warnings.get_ref();
+                self.parse::<bool>(location, "deny_warnings", value, "true or false");
+            }
+
+            if let Some(difficulty) = &exercise.difficulty {
+                let location = value_at(difficulty);
+                let expected = "one of easy, medium, hard";
+                let value = difficulty.get_ref();
+                self.parse::<Difficulty>(location, "difficulty", value, expected);
+            }
+
+            if let Some(estimated_minutes) = &exercise.estimated_minutes {
+                let location = value_at(estimated_minutes);
+                let value = estimated_minutes.get_ref();
+                self.parse::<u32>(
+                    location,
+                    "estimated_minutes",
+                    value,
+                    "a whole number of minutes",
+                );
+            }
+
+            if let Some(tags) = &exercise.tags {
+                let location = value_at(tags);
+                let value = tags.get_ref();
+                let parsed =
+                    self.parse::<Vec<String>>(location.clone(), "tags", value, "a list of strings");
+                if parsed.is_some_and(|tags| tags.iter().any(|tag| tag.trim().is_empty())) {
+                    self.report(location, "exercise has an empty tag");
+                }
+            }
+
+            if let Some(edition) = &exercise.edition {
+                let parsed: Result<Edition, _> =
+                    toml::Value::String(edition.get_ref().clone()).try_into();
+                if parsed.is_err() {
+                    let message = format!(
+                        "unknown edition '{}', expected one of: 2015, 2018, 2021, 2024",
+                        edition.get_ref()
+                    );
+                    self.report(at(edition), message);
+                }
+            }
+
+            match (&exercise.hint, &exercise.hints) {
+                (_, Some(hints)) => {
+                    let location = here(line_of(hints.start()));
+                    if hints.get_ref().is_empty() {
+                        self.report(location, "exercise has an empty `hints` list");
+                    } else if hints.get_ref().iter().any(|hint| hint.trim().is_empty()) {
+                        self.report(location, "exercise has an empty hint");
+                    }
+                }
+                (Some(hint), None) if !hint.get_ref().trim().is_empty() => {}
+                (Some(hint), None) => self.report(at(hint), "exercise has an empty hint"),
+                (None, None) => self.report(header.clone(), "exercise has no `hint` or `hints`"),
+            }
+        }
+
+        for (name, value) in &list.profiles {
+            let header = format!("[profiles.{name}]");
+            let line = source
+                .lines()
+                .position(|line| line.trim_start().starts_with(&header))
+                .map_or(0, |i| i + 1);
+            self.check_profile(name, value, here(line));
+        }
+
+        for include in &list.include {
+            let location = here(line_of(include.start()));
+            self.check_file(&normalize(&base.join(include.get_ref())), Some(location));
+        }
+    }
+
+    // A profile has to be a table of lists, with valid glob patterns. Its tags
+    // are checked once the whole course is known, by `check_profile_tags`.
+    fn check_profile(&mut self, name: &str, value: &toml::Value, location: Location) {
+        if let Some((_, first, _)) = self.profiles.iter().find(|(known, ..)| known == name) {
+            let message = format!("duplicate profile '{name}' (first defined at {first})");
+            self.report(location, message);
+            return;
+        }
+        let expected = "a table of `exercises`, `tags`, `exclude` and `exclude_tags` lists";
+        let field = format!("profiles.{name}");
+        let Some(profile) = self.parse::<Profile>(location.clone(), &field, value, expected) else {
+            return;
+        };
+        for pattern in profile.exercises.iter().chain(&profile.exclude) {
+            if let Err(e) = Pattern::new(pattern) {
+                let message = format!("invalid pattern '{pattern}' in profile '{name}': {e}");
+                self.report(location.clone(), message);
+            }
+        }
+        self.profiles.push((name.to_string(), location, profile));
+    }
+
+    // Every tag a profile selects or excludes should belong to an exercise,
+    // otherwise it's most likely a typo
+    fn check_profile_tags(&mut self, list: &ExerciseList) {
+        let mut unknown = Vec::new();
+        for (name, location, profile) in &self.profiles {
+            for tag in profile.tags.iter().chain(&profile.exclude_tags) {
+                if !list.exercises.iter().any(|e| e.has_tag(tag)) {
+                    let message =
+                        format!("profile '{name}' refers to tag '{tag}', which no exercise has");
+                    unknown.push((location.clone(), message));
+                }
+            }
+        }
+        for (location, message) in unknown {
+            self.report(location, message);
+        }
+    }
+
+    fn check_exercise_path(&mut self, path: &Path, location: Location) {
+        let path = normalize(path);
+        match fs::read_to_string(&path) {
+            Err(_) => {
+                let message = format!("{} does not exist", path.display());
+                self.report(location.clone(), message);
+            }
+            Ok(source) => {
+                if !Regex::new(I_AM_DONE_REGEX).unwrap().is_match(&source) {
+                    let message = format!("{} has no `I AM NOT DONE` marker", path.display());
+                    self.report(location.clone(), message);
+                }
+            }
+        }
+        if !self.paths.insert(path.clone()) {
+            let message = format!("{} is used by several exercises", path.display());
+            self.report(location, message);
+        }
+    }
+
+    // Every exercise file next to the course description should be part of
+    // the course
+    fn check_unlisted(&mut self, info: &Path) {
+        let base = info.parent().unwrap_or_else(|| Path::new(""));
+        let pattern =
+            Path::new(&Pattern::escape(&base.to_string_lossy())).join("exercises/**/*.rs");
+        let Ok(files) = glob(&pattern.to_string_lossy()) else {
+            return;
+        };
+        for file in files.flatten() {
+            let ignored = file
+                .file_name()
+                .is_some_and(|n| n == "mod.rs" || n == "build.rs")
+                || file.components().any(|c| c.as_os_str() == "target");
+            if !ignored && !self.paths.contains(&normalize(&file)) {
+                let message = format!("{} is not listed as an exercise", file.display());
+                let location = Location {
+                    file: info.to_path_buf(),
+                    line: 0,
+                };
+                self.report(location, message);
+            }
+        }
+    }
+}
+
+// Compile and run an exercise, without printing anything
+fn passes(exercise: &Exercise) -> Result<(), &'static str> {
+    match evaluate(exercise) {
+        Outcome::Success(_) => Ok(()),
+        Outcome::CompileError(_) => Err("does not compile"),
+        Outcome::RunError(_) => Err("does not run successfully"),
+    }
+}
+
+// Validate the course description at `info` and every file it includes, and
+// make sure rustlings can load it. All problems are printed, and `false` is
+// returned if there were any.
+// With `solutions`, every exercise additionally needs a reference solution in
+// `solutions/` that passes, unless the course ships no solutions at all.
+pub fn check_config(info: &Path, solutions: bool) -> bool {
+    let mut checker = Checker::default();
+    checker.check_file(info, None);
+    checker.check_unlisted(info);
+
+    // Anything the checks above missed still keeps rustlings from loading
+    // the course, which is reported rather than called good
+    let list = match ExerciseList::load(info) {
+        Ok(list) => Some(list),
+        Err(e) => {
+            if checker.problems.is_empty() {
+                let location = Location {
+                    file: info.to_path_buf(),
+                    line: 0,
+                };
+                checker.report(location, e.to_string());
+            }
+            None
+        }
+    };
+    if let Some(list) = &list {
+        checker.check_profile_tags(list);
+    }
+
+    let list = list.filter(|_| solutions && checker.problems.is_empty());
+    if list.is_some() && !has_solutions() {
+        println!("The course ships no reference solutions, so there are none to check.");
+    } else if let Some(list) = list {
+        for exercise in &list.exercises {
+            let location = checker.names[&exercise.name].clone();
+            let Some(solved) = solved(exercise) else {
+                let path = solution_path(exercise);
+                let message = format!("'{}' has no solution at {}", exercise.name, path.display());
+                checker.report(location, message);
+                continue;
+            };
+            println!("Checking {}...", exercise.name);
+            if let Err(reason) = passes(&solved) {
+                let message = format!("the solution of '{}' {reason}", exercise.name);
+                checker.report(location, message);
+            }
+        }
+    }
+
+    for problem in &checker.problems {
+        println!(
+            "{}: {}",
+            style(&problem.location).bold(),
+            style(&problem.message).red()
+        );
+    }
+    if checker.problems.is_empty() {
+        success!("{} looks good!", info.display());
+        true
+    } else {
+        warn!("Found {} problem(s)", checker.problems.len());
+        false
+    }
+}
@@ -0,0 +1,210 @@
+use crate::editor::{failure_location, Location};
+use indicatif::ProgressBar;
+use rustlings::exercise::{Exercise, ExerciseOutput};
+use rustlings::runner::Check;
+use rustlings::Error;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+// The debuggers rustlings can launch, through the wrappers rustup installs
+// to pretty-print Rust types
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Debugger {
+    Gdb,
+    Lldb,
+}
+
+impl FromStr for Debugger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gdb" | "rust-gdb" => Ok(Debugger::Gdb),
+            "lldb" | "rust-lldb" => Ok(Debugger::Lldb),
+            _ => Err(format!("unknown debugger '{s}', expected gdb or lldb")),
+        }
+    }
+}
+
+impl Debugger {
+    fn program(self) -> &'static str {
+        match self {
+            Debugger::Gdb => "rust-gdb",
+            Debugger::Lldb => "rust-lldb",
+        }
+    }
+
+    fn is_installed(self) -> bool {
+        Command::new(self.program())
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    // The command that starts `binary` with `args` in the debugger, stopped
+    // at `location` and at every panic
+    fn command(self, binary: &Path, args: &[String], location: Option<&Location>) -> Command {
+        let mut cmd = Command::new(self.program());
+        match self {
+            Debugger::Gdb => {
+                if let Some(location) = location {
+                    cmd.args(["-ex", &format!("break {location}")]);
+                }
+                cmd.args(["-ex", "break rust_panic", "-ex", "run", "--args"])
+                    .arg(binary)
+                    .args(args);
+            }
+            Debugger::Lldb => {
+                if let Some(location) = location {
+                    cmd.args([
+                        "-o",
+                        &format!(
+                            "breakpoint set --file {} --line {}",
+                            location.path.display(),
+                            location.line
+                        ),
+                    ]);
+                }
+                cmd.args(["-o", "breakpoint set --name rust_panic", "-o", "run", "--"])
+                    .arg(binary)
+                    .args(args);
+            }
+        }
+        cmd
+    }
+}
+
+// Where to stop: the first failing assertion when the tests are run, and
+// otherwise the `I AM NOT DONE` marker. A done exercise without failures
+// only stops at panics.
+fn breakpoint(exercise: &Exercise, output: Option<&ExerciseOutput>) -> Option<Location> {
+    if output.is_none() && exercise.looks_done() {
+        return None;
+    }
+    Some(failure_location(exercise, output))
+}
+
+// Build the exercise with debug info and debug it with `debugger`, or with
+// whichever of gdb and lldb is installed. Test exercises run only the tests
+// matching `filter`, one at a time. Returns whether the debugger exited
+// successfully.
+pub fn debug(
+    exercise: &Exercise,
+    filter: Option<&str>,
+    debugger: Option<Debugger>,
+) -> Result<bool, String> {
+    let debugger = match debugger {
+        Some(debugger) if debugger.is_installed() => debugger,
+        Some(debugger) => {
+            return Err(format!(
+                "`{}` didn't work, is the debugger installed?",
+                debugger.program()
+            ))
+        }
+        None => [Debugger::Gdb, Debugger::Lldb]
+            .into_iter()
+            .find(|debugger| debugger.is_installed())
+            .ok_or(
+                "Neither rust-gdb nor rust-lldb works, install gdb or lldb to debug exercises",
+            )?,
+    };
+
+    let progress_bar = ProgressBar::new_spinner();
+    progress_bar.set_message(format!("Compiling {exercise} for debugging..."));
+    progress_bar.enable_steady_tick(100);
+    let binary = exercise.compile_debug().map_err(|error| {
+        progress_bar.finish_and_clear();
+        match error {
+            Error::Compile(output) => format!(
+                "Compilation of {exercise} failed!, Compiler error message:\n\n{}",
+                output.stderr
+            ),
+            error => error.to_string(),
+        }
+    })?;
+
+    let mut args = Vec::new();
+    let mut failure = None;
+    let harness = exercise
+        .runner()
+        .is_ok_and(|runner| runner.check() == Check::Test);
+    if harness {
+        args.extend(filter.map(str::to_string));
+        args.push("--test-threads=1".to_string());
+        // A first run without the debugger finds the failing test
+        progress_bar.set_message(format!("Testing {exercise}..."));
+        let output = Command::new(&binary)
+            .args(&args)
+            .output()
+            .map_err(|e| format!("Could not run {}: {e}", binary.display()))?;
+        if !output.status.success() {
+            failure = Some(ExerciseOutput {
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            });
+        }
+        args.push("--nocapture".to_string());
+    }
+    progress_bar.finish_and_clear();
+
+    let location = breakpoint(exercise, failure.as_ref());
+    match &location {
+        Some(location) => println!("Stopping at {location} and at panics"),
+        None => println!("Stopping at panics"),
+    }
+    let status = debugger
+        .command(&binary, &args, location.as_ref())
+        .status()
+        .map_err(|e| format!("Could not start {}: {e}", debugger.program()))?;
+    Ok(status.success())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_debugger_commands() {
+        let location = Location {
+            path: PathBuf::from("exercises/algorithm/algorithm4.rs"),
+            line: 42,
+        };
+        let args = vec!["insert".to_string(), "--test-threads=1".to_string()];
+        let binary = Path::new("target/rustlings/algorithm4");
+
+        let gdb = Debugger::Gdb.command(binary, &args, Some(&location));
+        assert_eq!(gdb.get_program(), "rust-gdb");
+        assert_eq!(
+            gdb.get_args().collect::<Vec<_>>(),
+            [
+                "-ex",
+                "break exercises/algorithm/algorithm4.rs:42",
+                "-ex",
+                "break rust_panic",
+                "-ex",
+                "run",
+                "--args",
+                "target/rustlings/algorithm4",
+                "insert",
+                "--test-threads=1",
+            ]
+        );
+
+        let lldb = Debugger::Lldb.command(binary, &[], None);
+        assert_eq!(
+            lldb.get_args().collect::<Vec<_>>(),
+            [
+                "-o",
+                "breakpoint set --name rust_panic",
+                "-o",
+                "run",
+                "--",
+                "target/rustlings/algorithm4",
+            ]
+        );
+    }
+}
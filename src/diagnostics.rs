@@ -0,0 +1,164 @@
+use console::{strip_ansi_codes, style};
+use regex::Regex;
+use rustlings::exercise::{Exercise, ExerciseOutput};
+
+// The problems found in the output of a failed exercise: compiler errors by
+// their message, and failing tests by their name. Line numbers are left out
+// on purpose, since they move around with every edit, so the same error in
+// several places is counted instead.
+pub struct Diagnostics {
+    exercise: String,
+    problems: Vec<(String, usize)>,
+}
+
+impl Diagnostics {
+    pub fn from_output(exercise: &Exercise, output: &ExerciseOutput) -> Self {
+        let text = strip_ansi_codes(&format!("{}\n{}", output.stderr, output.stdout)).into_owned();
+        let error = Regex::new(r"^error(\[\w+\])?: (.+)$").unwrap();
+        let failed_test = Regex::new(r"^test (\S+) \.\.\. FAILED$").unwrap();
+
+        let mut problems = Vec::new();
+        for line in text.lines().map(str::trim_end) {
+            let problem = if let Some(captures) = failed_test.captures(line) {
+                format!("test {}", &captures[1])
+            } else if error.is_match(line) && !is_summary(line) {
+                line.to_string()
+            } else {
+                continue;
+            };
+            match problems.iter_mut().find(|(p, _)| *p == problem) {
+                Some((_, count)) => *count += 1,
+                None => problems.push((problem, 1)),
+            }
+        }
+        Diagnostics {
+            exercise: exercise.name.clone(),
+            problems,
+        }
+    }
+
+    fn count(&self, problem: &str) -> usize {
+        self.problems
+            .iter()
+            .find(|(p, _)| p == problem)
+            .map_or(0, |(_, count)| *count)
+    }
+}
+
+// What became of a problem between two runs
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Change {
+    Resolved,
+    StillFailing,
+    New,
+}
+
+// How many times every problem was resolved, left in place or introduced
+fn compare(previous: &Diagnostics, current: &Diagnostics) -> Vec<(Change, String, usize)> {
+    let mut changes = Vec::new();
+    for (problem, count) in &previous.problems {
+        let left = current.count(problem);
+        if left < *count {
+            changes.push((Change::Resolved, problem.clone(), count - left));
+        }
+    }
+    for (problem, count) in &current.problems {
+        let before = previous.count(problem);
+        if before > 0 {
+            changes.push((Change::StillFailing, problem.clone(), before.min(*count)));
+        }
+        if *count > before {
+            changes.push((Change::New, problem.clone(), count - before));
+        }
+    }
+    changes
+}
+
+// The closing lines of rustc and cargo, which say nothing about the problems
+fn is_summary(line: &str) -> bool {
+    ["aborting due to", "could not compile", "test failed"]
+        .iter()
+        .any(|summary| line.contains(summary))
+}
+
+// Print which problems the last change resolved, which ones it introduced and
+// which ones are still there, if the previous run was about the same exercise
+pub fn print_changes(previous: Option<&Diagnostics>, exercise: &Exercise, output: &ExerciseOutput) {
+    let Some(previous) = previous.filter(|previous| previous.exercise == exercise.name) else {
+        return;
+    };
+    let current = Diagnostics::from_output(exercise, output);
+    if previous.problems.is_empty() && current.problems.is_empty() {
+        return;
+    }
+    println!("Since your last change:");
+    for (change, problem, count) in compare(previous, &current) {
+        let label = match change {
+            Change::Resolved => style("resolved:     ").green(),
+            Change::StillFailing => style("still failing:").yellow(),
+            Change::New => style("new:          ").red(),
+        };
+        if count > 1 {
+            println!("  {label} {problem} (×{count})");
+        } else {
+            println!("  {label} {problem}");
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_problems_of_compiler_and_test_output() {
+        let exercise = Exercise {
+            name: "diagnosed".into(),
+            path: PathBuf::from("tests/fixture/failure/testFailure.rs"),
+            mode: "test".to_string(),
+            ..Exercise::default()
+        };
+        let output = ExerciseOutput {
+            stdout: "test tests::passes ... ok\ntest tests::fails ... FAILED\n".into(),
+            stderr: "\x1b[1merror[E0308]\x1b[0m: mismatched types\n --> a.rs:2:1\nerror[E0308]: mismatched types\nerror: aborting due to 2 previous errors\n".into(),
+        };
+        let diagnostics = Diagnostics::from_output(&exercise, &output);
+        assert_eq!(
+            diagnostics.problems,
+            [
+                ("error[E0308]: mismatched types".to_string(), 2),
+                ("test tests::fails".to_string(), 1)
+            ]
+        );
+
+        // Fixing one of two identical errors counts as resolving it
+        let fixed_one = ExerciseOutput {
+            stdout: "test tests::fails ... FAILED\n".into(),
+            stderr: "error[E0308]: mismatched types\nerror[E0599]: no method named `len`\n".into(),
+        };
+        let current = Diagnostics::from_output(&exercise, &fixed_one);
+        assert_eq!(
+            compare(&diagnostics, &current),
+            [
+                (
+                    Change::Resolved,
+                    "error[E0308]: mismatched types".to_string(),
+                    1
+                ),
+                (
+                    Change::StillFailing,
+                    "error[E0308]: mismatched types".to_string(),
+                    1
+                ),
+                (
+                    Change::New,
+                    "error[E0599]: no method named `len`".to_string(),
+                    1
+                ),
+                (Change::StillFailing, "test tests::fails".to_string(), 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use console::style;
+use rustlings::exercise::Exercise;
+use rustlings::pristine::{has_snapshot, pristine_source, PRISTINE_DIR};
+use rustlings::Error;
+use similar::{ChangeTag, DiffOp, TextDiff};
+use std::fmt::Display;
+use std::fs;
+use std::ops::Range;
+
+// The lines of the `#[cfg(test)]` modules in the given source, by index
+fn test_module_lines(source: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    let mut depth = 0;
+    for (i, line) in source.lines().enumerate() {
+        if start.is_none() {
+            if line.trim_start().starts_with("#[cfg(test)]") {
+                start = Some(i);
+            } else {
+                continue;
+            }
+        }
+        let opened = line.matches('{').count();
+        let closed = line.matches('}').count();
+        depth += opened as isize - closed as isize;
+        if depth <= 0 && (opened > 0 || closed > 0) {
+            ranges.extend(start.take().map(|start| start..i + 1));
+            depth = 0;
+        }
+    }
+    // A module that is never closed runs to the end of the file
+    ranges.extend(start.map(|start| start..source.lines().count()));
+    ranges
+}
+
+// The changes between two versions of an exercise
+struct SourceDiff {
+    original: String,
+    current: String,
+    tests_only: bool,
+}
+
+impl SourceDiff {
+    // The changes since the original version of the exercise
+    fn since_pristine(exercise: &Exercise, tests_only: bool) -> Result<Self, Error> {
+        Ok(SourceDiff {
+            original: pristine_source(exercise)?,
+            current: fs::read_to_string(&exercise.path)
+                .map_err(|e| Error::io("read", &exercise.path, e))?,
+            tests_only,
+        })
+    }
+
+    // The groups of changes with some context around them, leaving out the
+    // ones outside of the test modules if only those are of interest
+    fn hunks(&self) -> Vec<Vec<DiffOp>> {
+        let diff = TextDiff::from_lines(&self.original, &self.current);
+        let mut groups = diff.grouped_ops(3);
+        if self.tests_only {
+            let original_tests = test_module_lines(&self.original);
+            let current_tests = test_module_lines(&self.current);
+            let touches = |ranges: &[Range<usize>], changed: Range<usize>| {
+                ranges
+                    .iter()
+                    .any(|r| r.start < changed.end.max(changed.start + 1) && changed.start < r.end)
+            };
+            groups.retain(|ops| {
+                ops.iter().any(|op| {
+                    !matches!(op, DiffOp::Equal { .. })
+                        && (touches(&original_tests, op.old_range())
+                            || touches(&current_tests, op.new_range()))
+                })
+            });
+        }
+        groups
+    }
+
+    // The number of inserted and deleted lines
+    fn count(&self) -> (usize, usize) {
+        let diff = TextDiff::from_lines(&self.original, &self.current);
+        let (mut inserted, mut deleted) = (0, 0);
+        for ops in self.hunks() {
+            for op in &ops {
+                for change in diff.iter_changes(op) {
+                    match change.tag() {
+                        ChangeTag::Insert => inserted += 1,
+                        ChangeTag::Delete => deleted += 1,
+                        ChangeTag::Equal => {}
+                    }
+                }
+            }
+        }
+        (inserted, deleted)
+    }
+
+    fn print(&self, labels: (impl Display, impl Display)) {
+        let diff = TextDiff::from_lines(&self.original, &self.current);
+        println!("{}", style(format!("--- {}", labels.0)).bold());
+        println!("{}", style(format!("+++ {}", labels.1)).bold());
+        for ops in self.hunks() {
+            let (Some(first), Some(last)) = (ops.first(), ops.last()) else {
+                continue;
+            };
+            let old = first.old_range().start..last.old_range().end;
+            let new = first.new_range().start..last.new_range().end;
+            println!(
+                "{}",
+                style(format!(
+                    "@@ -{},{} +{},{} @@",
+                    old.start + 1,
+                    old.len(),
+                    new.start + 1,
+                    new.len()
+                ))
+                .cyan()
+            );
+            for op in &ops {
+                for change in diff.iter_changes(op) {
+                    let line = change.value().trim_end_matches('\n');
+                    match change.tag() {
+                        ChangeTag::Delete => println!("{}", style(format!("-{line}")).red()),
+                        ChangeTag::Insert => println!("{}", style(format!("+{line}")).green()),
+                        ChangeTag::Equal => println!(" {line}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Print a unified diff of the exercise against its original version.
+// Returns whether there were any changes.
+pub fn print_diff(exercise: &Exercise, tests_only: bool) -> Result<bool, Error> {
+    let diff = SourceDiff::since_pristine(exercise, tests_only)?;
+    if diff.hunks().is_empty() {
+        return Ok(false);
+    }
+    let path = exercise.path.display();
+    diff.print((format!("original {path}"), format!("current  {path}")));
+    Ok(true)
+}
+
+// Print a unified diff between two versions of an exercise, labelled with
+// where they come from. Returns whether there were any changes.
+pub fn print_changes_between(
+    original: &str,
+    current: &str,
+    labels: (impl Display, impl Display),
+) -> bool {
+    let diff = SourceDiff {
+        original: original.to_string(),
+        current: current.to_string(),
+        tests_only: false,
+    };
+    if diff.hunks().is_empty() {
+        return false;
+    }
+    diff.print(labels);
+    true
+}
+
+// Print how many lines were changed in every exercise of the course
+pub fn print_summary(exercises: &[Exercise], tests_only: bool) {
+    let mut changed = 0;
+    for exercise in exercises {
+        let (inserted, deleted) = match SourceDiff::since_pristine(exercise, tests_only) {
+            Ok(diff) => diff.count(),
+            Err(e) => {
+                println!("{:<17}\t{}", exercise.name, style(e).red());
+                continue;
+            }
+        };
+        if inserted + deleted == 0 {
+            continue;
+        }
+        changed += 1;
+        println!(
+            "{:<17}\t{}\t{}",
+            exercise.name,
+            style(format!("+{inserted}")).green(),
+            style(format!("-{deleted}")).red()
+        );
+    }
+    let what = if tests_only { " in their tests" } else { "" };
+    println!("{changed} of {} exercises changed{what}", exercises.len());
+    print_origin();
+}
+
+// Say so when the originals are only what git first saw, which a course
+// that was committed solved doesn't tell apart from the learner's work
+pub fn print_origin() {
+    if !has_snapshot() {
+        println!(
+            "The course ships no originals in {PRISTINE_DIR}, so exercises were compared with the version git first saw."
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_test_module_lines() {
+        let source = "fn main() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn works() {\n        assert!(true);\n    }\n}\n";
+        let twice = format!("{source}{source}");
+        assert_eq!(test_module_lines(&twice), [2..9, 11..18]);
+        assert!(test_module_lines("fn main() {}\n").is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::error::{output_of, Error};
+use crate::exercise::{rustc_color_args, Exercise, ExerciseOutput, RunInput, DEBUG_DIR};
+use crate::runner::{run_binary, stray_settings, Check, ExerciseRunner, InvalidSetting};
+use regex::Regex;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Exercises about documentation: the exercise is compiled as a library and
+// the examples in its doc comments are run by `rustdoc --test`
+pub(crate) struct Doctest;
+
+// A doc example that failed, with the lines of its code block
+#[derive(Debug, PartialEq)]
+pub struct FailingBlock {
+    // The item the example documents, like `add` or `Stack::push`
+    pub item: String,
+    // The lines of the opening and closing fences
+    pub start: usize,
+    pub end: usize,
+}
+
+// The library the examples are linked against. rustdoc only accepts it with
+// a `lib*.rlib` name, so it is kept with the debug builds.
+fn library(exercise: &Exercise) -> PathBuf {
+    Path::new(DEBUG_DIR).join(format!("lib{}.rlib", exercise.name))
+}
+
+// The public items of the source, with their line numbers, and whether
+// they come with a doc comment
+pub fn public_items(source: &str) -> Vec<(usize, String, bool)> {
+    let item = Regex::new(
+        r"^\s*pub\s+(?:unsafe\s+)?(?:fn|struct|enum|trait|const|static|type|mod|union)\s+(\w+)",
+    )
+    .unwrap();
+    let lines: Vec<&str> = source.lines().collect();
+    let mut items = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(captures) = item.captures(line) else {
+            continue;
+        };
+        // Attributes may sit between the doc comment and the item
+        let documented = lines[..i]
+            .iter()
+            .rev()
+            .map(|line| line.trim())
+            .find(|line| !line.starts_with("#[") || line.starts_with("#[doc"))
+            .is_some_and(|line| line.starts_with("///") || line.starts_with("#[doc"));
+        items.push((i + 1, captures[1].to_string(), documented));
+    }
+    items
+}
+
+// Fail like a compile error when fewer than `minimum` public items are
+// documented, listing the ones that still need a doc comment
+fn check_documented(exercise: &Exercise, minimum: usize) -> Result<(), Error> {
+    let source =
+        fs::read_to_string(&exercise.path).map_err(|e| Error::io("read", &exercise.path, e))?;
+    let items = public_items(&source);
+    let documented = items
+        .iter()
+        .filter(|(_, _, documented)| *documented)
+        .count();
+    if documented >= minimum {
+        return Ok(());
+    }
+    let mut stderr = format!(
+        "{documented} public item(s) of {exercise} are documented, but at least {minimum} need a doc comment.\n"
+    );
+    for (line, name, _) in items.iter().filter(|(_, _, documented)| !documented) {
+        let _ = writeln!(
+            stderr,
+            "  {}:{line}: `{name}` has no doc comment",
+            exercise.path.display()
+        );
+    }
+    Err(Error::Compile(ExerciseOutput {
+        stdout: String::new(),
+        stderr,
+    }))
+}
+
+// The doc examples rustdoc reports as failed. rustdoc names an example by
+// the line of its opening fence, the closing fence is looked up in `source`.
+pub fn failing_blocks(stdout: &str, source: &str) -> Vec<FailingBlock> {
+    let header = Regex::new(r"^---- .* - (.+) \(line (\d+)\) stdout ----$").unwrap();
+    let lines: Vec<&str> = source.lines().collect();
+    stdout
+        .lines()
+        .filter_map(|line| header.captures(line))
+        .filter_map(|captures| {
+            let start: usize = captures[2].parse().ok()?;
+            let end = lines
+                .iter()
+                .enumerate()
+                .skip(start)
+                .find(|(_, line)| {
+                    line.trim_start()
+                        .trim_start_matches(['/', '!', '*'])
+                        .trim_start()
+                        .starts_with("```")
+                })
+                .map_or(start, |(i, _)| i + 1);
+            Some(FailingBlock {
+                item: captures[1].to_string(),
+                start,
+                end,
+            })
+        })
+        .collect()
+}
+
+impl ExerciseRunner for Doctest {
+    fn compile(&self, exercise: &Exercise, _binary: &Path) -> Result<(), Error> {
+        fs::create_dir_all(DEBUG_DIR).map_err(|e| Error::io("create", DEBUG_DIR, e))?;
+        let mut cmd = Command::new("rustc");
+        cmd.args(["--crate-type", "lib", "--crate-name", &exercise.name])
+            .arg(&exercise.path)
+            .arg("-o")
+            .arg(library(exercise))
+            .args(rustc_color_args())
+            .args(["--edition", exercise.edition.as_str()]);
+        if exercise.denies_warnings() {
+            cmd.args(["-D", "warnings"]);
+        }
+        let output = output_of(&mut cmd)?;
+        if !output.status.success() {
+            return Err(Error::Compile(ExerciseOutput::from(&output)));
+        }
+        match exercise.min_documented {
+            Some(minimum) => check_documented(exercise, minimum),
+            None => Ok(()),
+        }
+    }
+
+    // Filters and flags of the learner are passed on to the test harness
+    fn run(
+        &self,
+        exercise: &Exercise,
+        _binary: &Path,
+        input: &RunInput,
+    ) -> Result<ExerciseOutput, Error> {
+        let mut cmd = Command::new("rustdoc");
+        cmd.arg("--test")
+            .arg(&exercise.path)
+            .args(["--crate-name", &exercise.name])
+            .args(["--edition", exercise.edition.as_str()])
+            .arg("--extern")
+            .arg(format!("{}={}", exercise.name, library(exercise).display()))
+            .args(rustc_color_args());
+        for arg in &input.args {
+            cmd.args(["--test-args", arg]);
+        }
+        run_binary(cmd, &RunInput::default()).map_err(|error| match error {
+            Error::Run(mut output) => {
+                let source = fs::read_to_string(&exercise.path).unwrap_or_default();
+                let blocks = failing_blocks(&output.stdout, &source);
+                if !blocks.is_empty() {
+                    output.stdout += "\nFailing doc examples:\n";
+                    for block in blocks {
+                        let _ = writeln!(
+                            output.stdout,
+                            "  {}:{}-{} (`{}`)",
+                            exercise.path.display(),
+                            block.start,
+                            block.end,
+                            block.item
+                        );
+                    }
+                }
+                Error::Run(output)
+            }
+            error => error,
+        })
+    }
+
+    fn success_message(&self) -> String {
+        "The code is compiling, and the examples in its docs pass!".to_string()
+    }
+
+    fn check(&self) -> Check {
+        Check::Test
+    }
+
+    fn validate(&self, exercise: &Exercise) -> Vec<InvalidSetting> {
+        stray_settings(exercise, &["command", "build"])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = r#"//! Numbers
+
+/// Adds two numbers
+///
+/// ```
+/// assert_eq!(doc1::add(1, 2), 3);
+/// ```
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+/// Doubles a number
+///
+/// ```
+/// let x = 2;
+/// assert_eq!(doc1::double(x), 5);
+/// ```
+#[inline]
+pub fn double(a: i32) -> i32 {
+    a * 2
+}
+
+#[derive(Debug)]
+pub struct Undocumented;
+"#;
+
+    #[test]
+    fn test_public_items() {
+        assert_eq!(
+            public_items(SOURCE),
+            [
+                (8, "add".to_string(), true),
+                (19, "double".to_string(), true),
+                (24, "Undocumented".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failing_blocks() {
+        let stdout = "running 2 tests\n\
+            test doc1.rs - add (line 5) ... ok\n\
+            test doc1.rs - double (line 14) ... FAILED\n\n\
+            failures:\n\n\
+            ---- doc1.rs - double (line 14) stdout ----\n\
+            Test executable failed (exit status: 101).\n";
+        assert_eq!(
+            failing_blocks(stdout, SOURCE),
+            [FailingBlock {
+                item: "double".to_string(),
+                start: 14,
+                end: 17,
+            }]
+        );
+    }
+}
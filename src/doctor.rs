@@ -0,0 +1,270 @@
+use console::{colors_enabled, style, Term};
+use rustlings::project::sysroot_src;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command};
+
+// How bad a failed check is
+#[derive(PartialEq)]
+enum Severity {
+    // Some exercises or commands can't work
+    Problem,
+    // Only an optional feature is affected
+    Warning,
+}
+
+// The outcome of one check: what was found, or what is wrong and how to fix it
+struct Check {
+    name: &'static str,
+    result: Result<String, (Severity, String, String)>,
+}
+
+impl Check {
+    fn ok(name: &'static str, found: impl Into<String>) -> Self {
+        Check {
+            name,
+            result: Ok(found.into()),
+        }
+    }
+
+    fn failed(
+        name: &'static str,
+        severity: Severity,
+        what: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Check {
+            name,
+            result: Err((severity, what.into(), fix.into())),
+        }
+    }
+
+    fn print(&self) {
+        match &self.result {
+            Ok(found) => println!("{} {}: {found}", style("✓").green(), self.name),
+            Err((severity, what, fix)) => {
+                let mark = match severity {
+                    Severity::Problem => style("✗").red(),
+                    Severity::Warning => style("!").yellow(),
+                };
+                println!("{mark} {}: {what}", self.name);
+                println!("    {} {fix}", style("fix:").bold());
+            }
+        }
+    }
+}
+
+// The first line a program prints with the given arguments, if it runs
+fn version(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Some(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
+fn check_program(
+    name: &'static str,
+    program: &str,
+    args: &[&str],
+    severity: Severity,
+    fix: &str,
+) -> Check {
+    match version(program, args) {
+        Some(version) => Check::ok(name, version),
+        None => {
+            let command = format!("{program} {}", args.join(" "));
+            Check::failed(name, severity, format!("`{command}` didn't work"), fix)
+        }
+    }
+}
+
+fn check_course(info: &Path) -> Check {
+    if info.exists() {
+        Check::ok("course", format!("{} found", info.display()))
+    } else {
+        Check::failed(
+            "course",
+            Severity::Problem,
+            format!("there is no {} here", info.display()),
+            "run rustlings from the rustlings directory, or pass `--info`",
+        )
+    }
+}
+
+fn check_sysroot_src() -> Check {
+    let fix = "run `rustup component add rust-src`, or point RUST_SRC_PATH at the library sources";
+    match sysroot_src() {
+        Ok(path) if path.exists() => Check::ok("rust-src", path.display().to_string()),
+        Ok(path) => Check::failed(
+            "rust-src",
+            Severity::Warning,
+            format!("{} doesn't exist, `rustlings lsp` needs it", path.display()),
+            fix,
+        ),
+        Err(e) => Check::failed("rust-src", Severity::Warning, e.to_string(), fix),
+    }
+}
+
+// Exercises are compiled to the current directory, and graded bundles are
+// unpacked in the temp dir
+fn check_writable(name: &'static str, dir: &Path) -> Check {
+    let probe = dir.join(format!(".rustlings-doctor-{}", process::id()));
+    match fs::write(&probe, "") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            Check::ok(name, format!("{} is writable", dir.display()))
+        }
+        Err(e) => Check::failed(
+            name,
+            Severity::Problem,
+            format!("can't write to {}: {e}", dir.display()),
+            format!("make {} writable for your user", dir.display()),
+        ),
+    }
+}
+
+// The number of directories below `dir`, each of which takes an inotify watch
+fn count_dirs(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    1 + entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| count_dirs(&entry.path()))
+        .sum::<usize>()
+}
+
+fn read_limit(name: &str) -> Option<usize> {
+    let path = Path::new("/proc/sys/fs/inotify").join(name);
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn check_inotify() -> Check {
+    let (Some(watches), Some(instances)) = (
+        read_limit("max_user_watches"),
+        read_limit("max_user_instances"),
+    ) else {
+        return Check::ok("file watching", "no inotify limits on this system");
+    };
+    let needed = count_dirs(Path::new("exercises"));
+    let found = format!("{watches} inotify watches and {instances} instances allowed");
+    if watches < needed {
+        Check::failed(
+            "file watching",
+            Severity::Problem,
+            format!("{found}, but `rustlings watch` needs {needed} watches"),
+            "raise the limit with `sudo sysctl fs.inotify.max_user_watches=524288`, \
+             or use `rustlings watch --poll`",
+        )
+    } else if instances == 0 {
+        Check::failed(
+            "file watching",
+            Severity::Problem,
+            format!("{found}, `rustlings watch` can't watch any file"),
+            "raise the limit with `sudo sysctl fs.inotify.max_user_instances=128`, \
+             or use `rustlings watch --poll`",
+        )
+    } else {
+        Check::ok("file watching", found)
+    }
+}
+
+fn check_terminal() -> Vec<Check> {
+    let term = Term::stdout();
+    if !term.is_term() {
+        return vec![Check::ok(
+            "terminal",
+            "output isn't a terminal, skipped colours and emoji",
+        )];
+    }
+    let colours = if colors_enabled() {
+        Check::ok("colours", "enabled")
+    } else {
+        Check::failed(
+            "colours",
+            Severity::Warning,
+            "disabled, compiler output will be hard to read",
+            "use a terminal with colour support, or set CLICOLOR_FORCE=1",
+        )
+    };
+    let emoji = if env::var("NO_EMOJI").is_ok() {
+        Check::ok("emoji", "replaced by plain symbols because NO_EMOJI is set")
+    } else if term.features().wants_emoji() {
+        Check::ok("emoji", "supported")
+    } else {
+        Check::failed(
+            "emoji",
+            Severity::Warning,
+            "your terminal may not show them",
+            "set NO_EMOJI=1 to use plain symbols instead",
+        )
+    };
+    vec![colours, emoji]
+}
+
+// Check everything rustlings relies on and print how to fix what is missing.
+// Returns `false` if there were problems beyond warnings.
+pub fn doctor(info: &Path) -> bool {
+    let temp_dir = env::temp_dir();
+    let mut checks = vec![
+        check_course(info),
+        check_program(
+            "rustc",
+            "rustc",
+            &["--version"],
+            Severity::Problem,
+            "install Rust from https://rustup.rs",
+        ),
+        check_program(
+            "cargo",
+            "cargo",
+            &["--version"],
+            Severity::Problem,
+            "install Rust from https://rustup.rs, cargo builds the clippy and build script exercises",
+        ),
+        check_program(
+            "clippy",
+            "cargo",
+            &["clippy", "--version"],
+            Severity::Problem,
+            "run `rustup component add clippy`",
+        ),
+        check_sysroot_src(),
+        check_program(
+            "git",
+            "git",
+            &["--version"],
+            Severity::Warning,
+            "install git to submit your work with `git push`",
+        ),
+        check_writable("exercise builds", Path::new(".")),
+        check_writable("temp dir", &temp_dir),
+        check_inotify(),
+    ];
+    checks.extend(check_terminal());
+
+    for check in &checks {
+        check.print();
+    }
+    let problems = checks
+        .iter()
+        .filter(|check| matches!(&check.result, Err((Severity::Problem, _, _))))
+        .count();
+    let warnings = checks.iter().filter(|check| check.result.is_err()).count() - problems;
+    println!();
+    if problems > 0 {
+        warn!(
+            "Found {} problem(s), rustlings won't work right until they're fixed",
+            problems
+        );
+    } else if warnings > 0 {
+        success!("Rustlings is ready to go, with {} warning(s)", warnings);
+    } else {
+        success!("Rustlings is ready to go!");
+    }
+    problems == 0
+}
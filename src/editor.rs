@@ -0,0 +1,178 @@
+use console::strip_ansi_codes;
+use regex::Regex;
+use rustlings::exercise::{Exercise, ExerciseOutput, State};
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Overrides how the editor is launched, like "code --goto {file}:{line}"
+const EDITOR_TEMPLATE_VAR: &str = "RUSTLINGS_EDITOR";
+
+// A line in one of the exercise files
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub line: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.path.display(), self.line)
+    }
+}
+
+// The place the learner should look at: the first error rustc points to or
+// the first failing assertion in the output, and otherwise the
+// `I AM NOT DONE` marker of the exercise
+pub fn failure_location(exercise: &Exercise, output: Option<&ExerciseOutput>) -> Location {
+    let from_output = output.and_then(|output| {
+        let text = strip_ansi_codes(&format!("{}\n{}", output.stderr, output.stdout)).into_owned();
+        let span = first_span(&text)?;
+        Some(Location {
+            path: resolve(&span.path, exercise)?,
+            line: span.line,
+        })
+    });
+    from_output.unwrap_or_else(|| {
+        let line = match exercise.state() {
+            Ok(State::Pending(context)) => context
+                .iter()
+                .find(|line| line.important)
+                .map_or(1, |line| line.number),
+            Ok(State::Done) | Err(_) => 1,
+        };
+        Location {
+            path: exercise.path.clone(),
+            line,
+        }
+    })
+}
+
+// The first ` --> file:line:column` span of a rustc diagnostic, or the first
+// `panicked at file:line:column` of a failing test
+fn first_span(text: &str) -> Option<Location> {
+    let span = Regex::new(r"(?m)(?:^\s*-->|panicked at) ([^\s:]+\.rs):(\d+)(?::\d+)?").unwrap();
+    let captures = span.captures(text)?;
+    Some(Location {
+        path: PathBuf::from(&captures[1]),
+        line: captures[2].parse().ok()?,
+    })
+}
+
+// The file a path in the output of the exercise refers to, if it exists.
+// rustc reports paths relative to the course, but cargo reports them
+// relative to the crate, which is the directory of the exercise.
+fn resolve(path: &Path, exercise: &Exercise) -> Option<PathBuf> {
+    let dir = exercise.path.parent().unwrap_or_else(|| Path::new(""));
+    [dir.join(path), path.to_path_buf()]
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+// The command line that opens the given location, from the template in
+// RUSTLINGS_EDITOR or from $VISUAL/$EDITOR. `{file}` and `{line}` in the
+// template are replaced with the location.
+fn editor_command(location: &Location) -> Option<Vec<String>> {
+    let template = match env::var(EDITOR_TEMPLATE_VAR) {
+        Ok(template) if !template.trim().is_empty() => template,
+        _ => {
+            let editor = env::var("VISUAL")
+                .or_else(|_| env::var("EDITOR"))
+                .ok()
+                .filter(|editor| !editor.trim().is_empty())?;
+            default_template(&editor)
+        }
+    };
+    let file = location.path.display().to_string();
+    let line = location.line.to_string();
+    let command = template
+        .split_whitespace()
+        .map(|part| part.replace("{file}", &file).replace("{line}", &line))
+        .collect();
+    Some(command)
+}
+
+// How to jump to a line with the usual editors
+fn default_template(editor: &str) -> String {
+    let program = editor.split_whitespace().next().unwrap_or_default();
+    let name = Path::new(program)
+        .file_stem()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let arguments = match name {
+        "vi" | "vim" | "nvim" | "gvim" | "emacs" | "emacsclient" | "nano" => "+{line} {file}",
+        "code" | "code-insiders" | "codium" => "--goto {file}:{line}",
+        "hx" | "helix" | "subl" | "zed" => "{file}:{line}",
+        _ => "{file}",
+    };
+    format!("{editor} {arguments}")
+}
+
+// Open the learner's editor at the given location and wait for it
+pub fn open_editor(location: &Location) -> io::Result<()> {
+    let Some(command) = editor_command(location) else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("set $VISUAL, $EDITOR or ${EDITOR_TEMPLATE_VAR} to open {location}"),
+        ));
+    };
+    Command::new(&command[0]).args(&command[1..]).status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_first_span_of_compiler_error() {
+        let stderr = "\x1b[1merror[E0308]\x1b[0m: mismatched types\n  \x1b[1m--> \x1b[0mexercises/intro/intro2.rs:8:26\n   |\n";
+        let text = strip_ansi_codes(stderr);
+        assert_eq!(
+            first_span(&text),
+            Some(Location {
+                path: PathBuf::from("exercises/intro/intro2.rs"),
+                line: 8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_first_span_of_failing_test() {
+        let stdout = "thread 'tests::it_works' panicked at exercises/tests/tests1.rs:19:9:\nassertion failed";
+        assert_eq!(first_span(stdout).map(|location| location.line), Some(19));
+        assert_eq!(first_span("test result: ok"), None);
+    }
+
+    #[test]
+    fn test_failure_location_resolves_crate_paths() {
+        let exercise = Exercise {
+            name: "clippy1".to_string(),
+            path: PathBuf::from("exercises/clippy/clippy1.rs"),
+            mode: "clippy".to_string(),
+            ..Exercise::default()
+        };
+        let output = |stderr: &str| ExerciseOutput {
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        };
+        let location = failure_location(&exercise, Some(&output("  --> clippy1.rs:3:5\n")));
+        assert_eq!(location.path, exercise.path);
+        assert_eq!(location.line, 3);
+        let location = failure_location(&exercise, Some(&output("  --> gone.rs:3:5\n")));
+        assert_eq!(location.path, exercise.path);
+    }
+
+    #[test]
+    fn test_default_template() {
+        assert_eq!(default_template("nvim"), "nvim +{line} {file}");
+        assert_eq!(
+            default_template("/usr/bin/code --wait"),
+            "/usr/bin/code --wait --goto {file}:{line}"
+        );
+        assert_eq!(default_template("hx"), "hx {file}:{line}");
+        assert_eq!(default_template("ed"), "ed {file}");
+    }
+}
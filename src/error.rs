@@ -0,0 +1,87 @@
+use crate::exercise::ExerciseOutput;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+// Everything that can go wrong while working on a course
+#[derive(Debug)]
+pub enum Error {
+    // A file couldn't be read or written. `action` is what was tried, like
+    // "read" or "write".
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        source: io::Error,
+    },
+    // A program rustlings relies on, like rustc or cargo, couldn't be started
+    Spawn {
+        program: String,
+        source: io::Error,
+    },
+    // The course description is invalid
+    Config(String),
+    // The arguments or input for an exercise don't fit its mode
+    Input(String),
+    // The exercise didn't compile, or clippy wasn't happy with it
+    Compile(ExerciseOutput),
+    // The exercise compiled, but running it or its tests failed
+    Run(ExerciseOutput),
+}
+
+impl Error {
+    pub fn io(action: &'static str, path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Error::Io {
+            action,
+            path: path.into(),
+            source,
+        }
+    }
+
+    // The output to show the learner. Errors that didn't come with the
+    // output of the compiler or the exercise are shown as their message.
+    pub fn into_output(self) -> ExerciseOutput {
+        match self {
+            Error::Compile(output) | Error::Run(output) => output,
+            error => ExerciseOutput {
+                stdout: String::new(),
+                stderr: error.to_string(),
+            },
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::Io {
+                action,
+                path,
+                source,
+            } => write!(f, "Could not {action} {}: {source}", path.display()),
+            Error::Spawn { program, source } => {
+                write!(f, "Could not run `{program}`, is it installed? {source}")
+            }
+            Error::Config(message) | Error::Input(message) => f.write_str(message),
+            Error::Compile(_) => f.write_str("The exercise didn't compile"),
+            Error::Run(_) => f.write_str("The exercise didn't run successfully"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } | Error::Spawn { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// Run the command to completion and collect its output
+pub(crate) fn output_of(cmd: &mut Command) -> Result<Output, Error> {
+    cmd.output().map_err(|source| Error::Spawn {
+        program: cmd.get_program().to_string_lossy().into_owned(),
+        source,
+    })
+}
@@ -0,0 +1,34 @@
+use crate::exercise::{Exercise, ExerciseOutput};
+use crate::runner::Check;
+
+// What came out of compiling and running an exercise
+pub enum Outcome {
+    // The exercise compiled and ran, or passed its tests
+    Success(ExerciseOutput),
+    // The exercise didn't compile, or clippy wasn't happy with it
+    CompileError(ExerciseOutput),
+    // The exercise compiled, but running it or its tests failed
+    RunError(ExerciseOutput),
+}
+
+// Compile and run the given Exercise according to its mode, the way
+// `rustlings verify` checks it
+pub fn evaluate(exercise: &Exercise) -> Outcome {
+    let compilation = match exercise.compile() {
+        Ok(compilation) => compilation,
+        Err(error) => return Outcome::CompileError(error.into_output()),
+    };
+    if exercise
+        .runner()
+        .is_ok_and(|runner| runner.check() == Check::CompileOnly)
+    {
+        return Outcome::Success(ExerciseOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+    }
+    match compilation.run() {
+        Ok(output) => Outcome::Success(output),
+        Err(error) => Outcome::RunError(error.into_output()),
+    }
+}
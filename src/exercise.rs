@@ -1,18 +1,32 @@
+use crate::error::Error;
+use crate::runner::{self, ExerciseRunner};
+use glob::Pattern;
 use regex::Regex;
 use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
 use std::env;
 use std::fmt::{self, Display, Formatter};
-use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::fs::{self, remove_file};
+use std::path::{Component, Path, PathBuf};
+use std::process::{self, Output};
+use std::str::FromStr;
+use std::sync::Arc;
 
 const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
-const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
-const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
+pub const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
-const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
-const BUILD_SCRIPT_CARGO_TOML_PATH: &str = "./exercises/tests/Cargo.toml";
+// Where exercises built for debuggers are kept, one binary per exercise
+pub const DEBUG_DIR: &str = "target/rustlings";
+
+// Compiler output is colored, unless NO_COLOR asks for plain text that
+// editors can parse
+pub(crate) fn rustc_color_args() -> &'static [&'static str] {
+    if env::var_os("NO_COLOR").is_some() {
+        &["--color", "never"]
+    } else {
+        RUSTC_COLOR_ARGS
+    }
+}
 
 // Get a temporary file name that is hopefully unique
 #[inline]
@@ -25,37 +39,285 @@ fn temp_file() -> String {
     format!("./temp_{}_{thread_id}", process::id())
 }
 
-// The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+// The Rust edition an exercise is written in.
+#[derive(Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Edition {
+    #[serde(rename = "2015")]
+    E2015,
+    #[serde(rename = "2018")]
+    E2018,
+    #[serde(rename = "2021")]
+    #[default]
+    E2021,
+    #[serde(rename = "2024")]
+    E2024,
+}
+
+impl Edition {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Edition::E2015 => "2015",
+            Edition::E2018 => "2018",
+            Edition::E2021 => "2021",
+            Edition::E2024 => "2024",
+        }
+    }
+}
+
+// How hard an exercise is expected to be.
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
-pub enum Mode {
-    // Indicates that the exercise should be compiled as a binary
-    Compile,
-    // Indicates that the exercise should be compiled as a test harness
-    Test,
-    // Indicates that the exercise should be linted with clippy
-    Clippy,
-    // Indicates that the exercise should be run using cargo with build script
-    BuildScript,
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        f.pad(name)
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!(
+                "unknown difficulty '{s}', expected one of: easy, medium, hard"
+            )),
+        }
+    }
 }
 
 #[derive(Deserialize)]
 pub struct ExerciseList {
+    // Other info.toml-like files whose exercises are appended to this list.
+    // Their paths are relative to the file including them.
+    #[serde(default)]
+    pub include: Vec<PathBuf>,
+    #[serde(default)]
     pub exercises: Vec<Exercise>,
+    // Named subsets of the course, selected with `--profile`
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    // Whether warnings fail the exercises of this file and the files it
+    // includes, unless they say otherwise
+    pub deny_warnings: Option<bool>,
+}
+
+impl ExerciseList {
+    // Read the given info.toml file together with everything it includes.
+    // Exercise paths are resolved relative to the file declaring them, so a
+    // track can keep its own info.toml next to its exercises.
+    pub fn load(path: &Path) -> Result<ExerciseList, Error> {
+        let mut list = ExerciseList {
+            include: Vec::new(),
+            exercises: Vec::new(),
+            profiles: BTreeMap::new(),
+            deny_warnings: None,
+        };
+        list.load_file(path, None, &mut HashSet::new())?;
+        Ok(list)
+    }
+
+    // Load the course like `load`, keeping only the exercises of `profile`
+    pub fn load_profile(path: &Path, profile: Option<&str>) -> Result<ExerciseList, Error> {
+        let mut list = ExerciseList::load(path)?;
+        if let Some(profile) = profile {
+            list.select_profile(profile)?;
+        }
+        Ok(list)
+    }
+
+    // `deny_warnings` is the setting inherited from the including file
+    fn load_file(
+        &mut self,
+        path: &Path,
+        deny_warnings: Option<bool>,
+        seen: &mut HashSet<PathBuf>,
+    ) -> Result<(), Error> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| Error::io("read", path, e))?;
+        if !seen.insert(canonical) {
+            return Err(Error::Config(format!(
+                "{} is included more than once",
+                path.display()
+            )));
+        }
+
+        let toml_str = fs::read_to_string(path).map_err(|e| Error::io("read", path, e))?;
+        let file: ExerciseList = toml::from_str(&toml_str)
+            .map_err(|e| Error::Config(format!("Could not parse {}: {e}", path.display())))?;
+
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        let deny_warnings = file.deny_warnings.or(deny_warnings);
+        self.deny_warnings = self.deny_warnings.or(deny_warnings);
+        self.exercises
+            .extend(file.exercises.into_iter().map(|mut exercise| {
+                exercise.path = normalize(&base.join(&exercise.path));
+                exercise.deny_warnings = exercise.deny_warnings.or(deny_warnings);
+                exercise
+            }));
+        self.profiles.extend(file.profiles);
+        for include in file.include {
+            let include = normalize(&base.join(include));
+            self.load_file(&include, deny_warnings, seen)?;
+            self.include.push(include);
+        }
+        Ok(())
+    }
+
+    // Keep only the exercises selected by the named profile
+    pub fn select_profile(&mut self, name: &str) -> Result<(), Error> {
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            Error::Input(if known.is_empty() {
+                format!("Unknown profile '{name}', no profiles are defined")
+            } else {
+                format!(
+                    "Unknown profile '{name}', available profiles: {}",
+                    known.join(", ")
+                )
+            })
+        })?;
+        let profile = profile.compile()?;
+        self.exercises.retain(|e| profile.matches(e));
+        Ok(())
+    }
+
+    // Keep only the exercises whose names match one of the glob patterns.
+    // Every pattern has to match at least one exercise.
+    pub fn focus(&mut self, patterns: &[String]) -> Result<(), Error> {
+        let mut compiled = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            let glob = Pattern::new(pattern)
+                .map_err(|e| Error::Input(format!("Invalid pattern '{pattern}': {e}")))?;
+            if !self.exercises.iter().any(|e| glob.matches(&e.name)) {
+                return Err(Error::Input(format!("No exercise found for '{pattern}'!")));
+            }
+            compiled.push(glob);
+        }
+        self.exercises
+            .retain(|e| compiled.iter().any(|glob| glob.matches(&e.name)));
+        Ok(())
+    }
+}
+
+// A named subset of the course, declared as `[profiles.<name>]` in info.toml.
+// An exercise is part of the profile when it matches the `exercises` patterns
+// or `tags` (or both are empty), and doesn't match `exclude` or `exclude_tags`.
+#[derive(Deserialize, Default, Debug)]
+pub struct Profile {
+    // Glob patterns matching the names of the exercises to keep
+    #[serde(default)]
+    pub exercises: Vec<String>,
+    // Tags or tracks of the exercises to keep
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Glob patterns matching the names of the exercises to leave out
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    // Tags or tracks of the exercises to leave out
+    #[serde(default)]
+    pub exclude_tags: Vec<String>,
+}
+
+impl Profile {
+    fn compile(&self) -> Result<CompiledProfile<'_>, Error> {
+        let patterns = |globs: &[String]| {
+            globs
+                .iter()
+                .map(|g| {
+                    Pattern::new(g)
+                        .map_err(|e| Error::Config(format!("Invalid pattern '{g}': {e}")))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(CompiledProfile {
+            profile: self,
+            exercises: patterns(&self.exercises)?,
+            exclude: patterns(&self.exclude)?,
+        })
+    }
+}
+
+struct CompiledProfile<'a> {
+    profile: &'a Profile,
+    exercises: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl CompiledProfile<'_> {
+    fn matches(&self, exercise: &Exercise) -> bool {
+        let selected = (self.exercises.is_empty() && self.profile.tags.is_empty())
+            || self.exercises.iter().any(|p| p.matches(&exercise.name))
+            || self.profile.tags.iter().any(|t| exercise.has_tag(t));
+        let excluded = self.exclude.iter().any(|p| p.matches(&exercise.name))
+            || self
+                .profile
+                .exclude_tags
+                .iter()
+                .any(|t| exercise.has_tag(t));
+        selected && !excluded
+    }
+}
+
+// Drop `.` components, so that paths from included files can still be
+// compared with the ones reported by the file watcher
+pub fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
 }
 
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Clone, Debug, Default)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
     // The path to the file containing the exercise's source code
     pub path: PathBuf,
-    // The mode of the exercise (Test, Compile, or Clippy)
-    pub mode: Mode,
+    // The mode of the exercise, which names the runner that builds and runs
+    // it, like `compile`, `test`, `clippy` or one added with `register`
+    pub mode: String,
+    // For custom exercises, the command that runs the exercise and the one
+    // building it first, if any
+    pub command: Option<String>,
+    pub build: Option<String>,
+    // For doctest exercises, how many public items need a doc comment
+    pub min_documented: Option<usize>,
+    // Whether warnings fail the exercise like errors do, taken from the
+    // course when not given
+    pub deny_warnings: Option<bool>,
     // The hint text associated with the exercise
+    #[serde(default)]
     pub hint: String,
+    // Hints of increasing strength, revealed one at a time. Takes the
+    // place of `hint` when given.
+    #[serde(default)]
+    pub hints: Vec<String>,
+    // How hard the exercise is, if the course author rated it
+    pub difficulty: Option<Difficulty>,
+    // Free-form topics used to build custom exercise sets
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // The rough number of minutes a learner needs to solve the exercise
+    pub estimated_minutes: Option<u32>,
+    // The edition the exercise is compiled with, 2021 unless given
+    #[serde(default)]
+    pub edition: Edition,
 }
 
 // An enum to track of the state of an Exercise.
@@ -82,16 +344,32 @@ pub struct ContextLine {
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
+    runner: Arc<dyn ExerciseRunner>,
     _handle: FileHandle,
 }
 
 impl<'a> CompiledExercise<'a> {
     // Run the compiled exercise
-    pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        self.exercise.run()
+    pub fn run(&self) -> Result<ExerciseOutput, Error> {
+        self.run_with(&RunInput::default())
+    }
+
+    // Run the compiled exercise with extra arguments or input
+    pub fn run_with(&self, input: &RunInput) -> Result<ExerciseOutput, Error> {
+        self.runner
+            .run(self.exercise, Path::new(&temp_file()), input)
     }
 }
 
+// What the learner passes on to a run of an exercise
+#[derive(Default, Debug)]
+pub struct RunInput {
+    // Program arguments, or libtest filters and flags for test exercises
+    pub args: Vec<String>,
+    // A file to read the standard input of the program from
+    pub stdin: Option<PathBuf>,
+}
+
 // A representation of an already executed binary
 #[derive(Debug)]
 pub struct ExerciseOutput {
@@ -101,6 +379,15 @@ pub struct ExerciseOutput {
     pub stderr: String,
 }
 
+impl From<&Output> for ExerciseOutput {
+    fn from(output: &Output) -> Self {
+        ExerciseOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }
+    }
+}
+
 struct FileHandle;
 
 impl Drop for FileHandle {
@@ -110,149 +397,75 @@ impl Drop for FileHandle {
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
-        let cmd = match self.mode {
-            Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Clippy => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
-                // To support the ability to run the clippy exercises, build
-                // an executable, in addition to running clippy. With a
-                // compilation failure, this would silently fail. But we expect
-                // clippy to reflect the same failure while compiling later.
-                Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(RUSTC_EDITION_ARGS)
-                    .output()
-                    .expect("Failed to compile!");
-                // Due to an issue with Clippy, a cargo clean is required to catch all lints.
-                // See https://github.com/rust-lang/rust-clippy/issues/2604
-                // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
-                // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .output()
-                    .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
-                    .output()
-            },
-            Mode::BuildScript => {
-                let cargo_toml = format!(
-                    r#"[package]
+    // The manifest rustlings writes for the exercises built with cargo, next
+    // to the exercise so that copies elsewhere, like the reference solutions,
+    // are built on their own
+    pub(crate) fn cargo_toml_path(&self) -> PathBuf {
+        self.path.with_file_name("Cargo.toml")
+    }
+
+    pub(crate) fn write_cargo_toml(&self) -> Result<(), Error> {
+        let cargo_toml = format!(
+            r#"[package]
 name = "{}"
 version = "0.0.1"
-edition = "2021"
+edition = "{}"
 [[bin]]
 name = "{}"
 path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(BUILD_SCRIPT_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
-
-                Command::new("cargo")
-                    .args(&["test", "--manifest-path", BUILD_SCRIPT_CARGO_TOML_PATH])
-                    .output()
-            }
-        }
-        .expect("Failed to run 'compile' command.");
+            self.name,
+            self.edition.as_str(),
+            self.name,
+            self.name
+        );
+        let manifest = self.cargo_toml_path();
+        fs::write(&manifest, cargo_toml).map_err(|e| Error::io("write", manifest, e))
+    }
 
-        if cmd.status.success() {
-            Ok(CompiledExercise {
+    // The runner registered for the mode of the exercise
+    pub fn runner(&self) -> Result<Arc<dyn ExerciseRunner>, Error> {
+        runner::get(&self.mode)
+    }
+
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, Error> {
+        let runner = self.runner()?;
+        match runner.compile(self, Path::new(&temp_file())) {
+            Ok(()) => Ok(CompiledExercise {
                 exercise: self,
+                runner,
                 _handle: FileHandle,
-            })
-        } else {
-            clean();
-            Err(ExerciseOutput {
-                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
-            })
+            }),
+            Err(error) => {
+                clean();
+                Err(error)
+            }
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
-            Mode::BuildScript => return Ok(ExerciseOutput {
-                stdout: "".to_string(),
-                stderr: "".to_string(),
-            }),
-            _ => "",
-        };
-        println!("pa={}", temp_file());
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
-
-        let output = ExerciseOutput {
-            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
-        };
-
-        if cmd.status.success() {
-            Ok(output)
-        } else {
-            Err(output)
-        }
+    // Where `compile_debug` puts the binary, the same for every build so that
+    // debugger configurations can point at it
+    pub fn debug_path(&self) -> PathBuf {
+        Path::new(DEBUG_DIR).join(&self.name)
     }
 
-    pub fn state(&self) -> State {
-        let mut source_file =
-            File::open(&self.path).expect("We were unable to open the exercise file!");
+    // Compile the exercise with debug info to `debug_path`, the way its
+    // runner does it
+    pub fn compile_debug(&self) -> Result<PathBuf, Error> {
+        let target = self.debug_path();
+        fs::create_dir_all(DEBUG_DIR).map_err(|e| Error::io("create", DEBUG_DIR, e))?;
+        self.runner()?.compile_debug(self, &target)?;
+        Ok(target)
+    }
 
-        let source = {
-            let mut s = String::new();
-            source_file
-                .read_to_string(&mut s)
-                .expect("We were unable to read the exercise file!");
-            s
-        };
+    pub fn state(&self) -> Result<State, Error> {
+        let source =
+            fs::read_to_string(&self.path).map_err(|e| Error::io("read", &self.path, e))?;
 
         let re = Regex::new(I_AM_DONE_REGEX).unwrap();
 
-        if !re.is_match(&source) {
-            return State::Done;
-        }
-
-        let matched_line_index = source
-            .lines()
-            .enumerate()
-            .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
-            .expect("This should not happen at all");
+        let Some(matched_line_index) = source.lines().position(|line| re.is_match(line)) else {
+            return Ok(State::Done);
+        };
 
         let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
         let max_line = matched_line_index + CONTEXT;
@@ -268,7 +481,7 @@ path = "{}.rs""#,
             })
             .collect();
 
-        State::Pending(context)
+        Ok(State::Pending(context))
     }
 
     // Check that the exercise looks to be solved using self.state()
@@ -278,34 +491,81 @@ path = "{}.rs""#,
     // The only other way to truly check this would to compile and run
     // the exercise; which would be both costly and counterintuitive
     pub fn looks_done(&self) -> bool {
-        self.state() == State::Done
+        matches!(self.state(), Ok(State::Done))
+    }
+
+    // Whether rustc builds of the exercise fail on warnings
+    pub fn denies_warnings(&self) -> bool {
+        self.deny_warnings.unwrap_or(false)
+    }
+
+    // The hints of the exercise, from the gentlest to the strongest
+    pub fn hint_levels(&self) -> Vec<&str> {
+        if self.hints.is_empty() {
+            vec![self.hint.as_str()]
+        } else {
+            self.hints.iter().map(String::as_str).collect()
+        }
+    }
+
+    // The topic of the exercise, which is the name of the directory it lives in
+    pub fn track(&self) -> Option<&str> {
+        self.path.parent()?.file_name()?.to_str()
+    }
+
+    // Check whether the exercise is tagged with the given tag.
+    // Every exercise is implicitly tagged with its track.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim();
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+            || self.track().is_some_and(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+// Sum up the estimated minutes of all exercises that don't look done yet.
+// Exercises without an estimate don't count towards the total.
+pub fn estimated_minutes_remaining<'a>(exercises: impl IntoIterator<Item = &'a Exercise>) -> u32 {
+    exercises
+        .into_iter()
+        .filter(|e| !e.looks_done())
+        .filter_map(|e| e.estimated_minutes)
+        .sum()
+}
+
+// Render a number of minutes the way a human would say it, e.g. "1h 5min"
+pub fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}min"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h {m}min"),
     }
 }
 
 impl Display for Exercise {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}", self.path.to_str().unwrap())
+        write!(f, "{}", self.path.display())
     }
 }
 
 #[inline]
 fn clean() {
-    let _ignored = remove_file(&temp_file());
+    let _ignored = remove_file(temp_file());
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::fs::File;
     use std::path::Path;
 
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
+        File::create(temp_file()).unwrap();
         let exercise = Exercise {
             name: String::from("example"),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::from(""),
+            mode: "compile".to_string(),
+            ..Exercise::default()
         };
         let compiled = exercise.compile().unwrap();
         drop(compiled);
@@ -317,8 +577,8 @@ mod test {
         let exercise = Exercise {
             name: "pending_exercise".into(),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
+            mode: "compile".to_string(),
+            ..Exercise::default()
         };
 
         let state = exercise.state();
@@ -350,7 +610,7 @@ mod test {
             },
         ];
 
-        assert_eq!(state, State::Pending(expected));
+        assert_eq!(state.unwrap(), State::Pending(expected));
     }
 
     #[test]
@@ -358,11 +618,32 @@ mod test {
         let exercise = Exercise {
             name: "finished_exercise".into(),
             path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
+            mode: "compile".to_string(),
+            ..Exercise::default()
         };
 
-        assert_eq!(exercise.state(), State::Done);
+        assert_eq!(exercise.state().unwrap(), State::Done);
+    }
+
+    #[test]
+    fn test_errors_are_typed() {
+        let mut exercise = Exercise {
+            name: "missing_exercise".into(),
+            path: PathBuf::from("tests/fixture/state/missing_exercise.rs"),
+            mode: "compile".to_string(),
+            ..Exercise::default()
+        };
+        assert!(matches!(
+            exercise.state(),
+            Err(Error::Io { action: "read", .. })
+        ));
+        assert!(!exercise.looks_done());
+
+        exercise.path = PathBuf::from("tests/fixture/failure/compFailure.rs");
+        let Err(Error::Compile(output)) = exercise.compile() else {
+            panic!("compFailure.rs should fail to compile");
+        };
+        assert!(output.stderr.contains("error"));
     }
 
     #[test]
@@ -370,10 +651,76 @@ mod test {
         let exercise = Exercise {
             name: "exercise_with_output".into(),
             path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
-            mode: Mode::Test,
-            hint: String::new(),
+            mode: "test".to_string(),
+            ..Exercise::default()
         };
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
+
+    #[test]
+    fn test_track_is_an_implicit_tag() {
+        let exercise = Exercise {
+            name: "tagged".into(),
+            path: PathBuf::from("exercises/move_semantics/move_semantics1.rs"),
+            mode: "compile".to_string(),
+            difficulty: Some(Difficulty::Easy),
+            tags: vec!["ownership".into()],
+            estimated_minutes: Some(5),
+            ..Exercise::default()
+        };
+        assert_eq!(exercise.track(), Some("move_semantics"));
+        assert!(exercise.has_tag("Ownership"));
+        assert!(exercise.has_tag("move_semantics"));
+        assert!(!exercise.has_tag("iterators"));
+    }
+
+    #[test]
+    fn test_hint_levels_fall_back_to_hint() {
+        let mut exercise = Exercise {
+            name: "hinted".into(),
+            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
+            mode: "compile".to_string(),
+            hint: "the only hint".into(),
+            ..Exercise::default()
+        };
+        assert_eq!(exercise.hint_levels(), ["the only hint"]);
+
+        exercise.hints = vec!["nudge".into(), "near-solution".into()];
+        assert_eq!(exercise.hint_levels(), ["nudge", "near-solution"]);
+    }
+
+    #[test]
+    fn test_format_minutes() {
+        assert_eq!(format_minutes(45), "45min");
+        assert_eq!(format_minutes(120), "2h");
+        assert_eq!(format_minutes(125), "2h 5min");
+    }
+
+    #[test]
+    fn test_load_with_includes_and_profiles() {
+        let mut list = ExerciseList::load(Path::new("tests/fixture/include/info.toml")).unwrap();
+        let paths: Vec<&Path> = list.exercises.iter().map(|e| e.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                Path::new("tests/fixture/include/root_exercise.rs"),
+                Path::new("tests/fixture/include/track/track_exercise.rs"),
+            ]
+        );
+
+        list.select_profile("root_only").unwrap();
+        let names: Vec<&str> = list.exercises.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["root_exercise"]);
+        assert!(list.select_profile("unknown").is_err());
+    }
+
+    #[test]
+    fn test_focus_on_exercises() {
+        let mut list = ExerciseList::load(Path::new("tests/fixture/include/info.toml")).unwrap();
+        assert!(list.focus(&["nothing*".into()]).is_err());
+        list.focus(&["track_*".into()]).unwrap();
+        let names: Vec<&str> = list.exercises.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["track_exercise"]);
+    }
 }
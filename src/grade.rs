@@ -0,0 +1,94 @@
+use crate::error::Error;
+use crate::exercise::{Exercise, ExerciseOutput};
+use crate::hints::RevealedHints;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseCheckList {
+    pub exercises: Vec<ExerciseResult>,
+    pub user_name: Option<String>,
+    pub statistics: ExerciseStatistics,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseResult {
+    pub name: String,
+    pub result: bool,
+    // How many hint levels were revealed before grading
+    #[serde(default)]
+    pub hints_used: usize,
+    // 1.0 for a solved exercise, minus the penalty for the hints used
+    #[serde(default)]
+    pub score: f32,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseStatistics {
+    pub total_exercations: usize,
+    pub total_succeeds: usize,
+    pub total_failures: usize,
+    pub total_time: u32,
+    #[serde(default)]
+    pub total_score: f32,
+}
+
+// Run every exercise like the CI grader does and collect the results. Each
+// revealed hint costs `hint_penalty` of the exercise's score. `graded` is
+// called with every exercise as soon as it's done, along with what running
+// it gave.
+pub async fn grade(
+    exercises: Vec<Exercise>,
+    revealed_hints: &RevealedHints,
+    hint_penalty: f32,
+    graded: impl Fn(&Exercise, &Result<ExerciseOutput, Error>) + Send + Sync + 'static,
+) -> ExerciseCheckList {
+    let start = Instant::now();
+    let graded = Arc::new(graded);
+
+    let mut tasks = vec![];
+    for exercise in exercises {
+        let name = exercise.name.clone();
+        let hints_used = revealed_hints.used(&exercise);
+        let score = (1.0 - hint_penalty * hints_used as f32).max(0.0);
+        let graded = Arc::clone(&graded);
+        let t = tokio::task::spawn(async move {
+            let output = exercise.compile().and_then(|compilation| compilation.run());
+            graded(&exercise, &output);
+            output.is_ok()
+        });
+        tasks.push((name, hints_used, score, t));
+    }
+
+    let mut check_list = ExerciseCheckList {
+        exercises: vec![],
+        user_name: None,
+        statistics: ExerciseStatistics {
+            total_exercations: tasks.len(),
+            total_succeeds: 0,
+            total_failures: 0,
+            total_time: 0,
+            total_score: 0.0,
+        },
+    };
+    for (name, hints_used, score, task) in tasks {
+        // An exercise whose runner panicked failed like any other
+        let result = task.await.unwrap_or(false);
+        let score = if result { score } else { 0.0 };
+        check_list.exercises.push(ExerciseResult {
+            name,
+            result,
+            hints_used,
+            score,
+        });
+        if result {
+            check_list.statistics.total_succeeds += 1;
+            check_list.statistics.total_score += score;
+        } else {
+            check_list.statistics.total_failures += 1;
+        }
+    }
+    check_list.statistics.total_time = start.elapsed().as_secs() as u32;
+    check_list
+}
@@ -0,0 +1,57 @@
+use crate::error::Error;
+use crate::exercise::Exercise;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const HINTS_PATH: &str = ".rustlings/hints.json";
+
+// How many hint levels the learner has revealed so far, per exercise name.
+// Kept on disk so that grading can take the hints into account.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RevealedHints {
+    levels: BTreeMap<String, usize>,
+}
+
+impl RevealedHints {
+    // A missing or unreadable file counts as no hints revealed
+    pub fn load() -> Self {
+        let levels = fs::read_to_string(HINTS_PATH)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        RevealedHints { levels }
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        if let Some(dir) = Path::new(HINTS_PATH).parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::io("create", dir, e))?;
+        }
+        let json = serde_json::to_string_pretty(&self.levels)
+            .map_err(|e| Error::io("write", HINTS_PATH, e.into()))?;
+        fs::write(HINTS_PATH, json).map_err(|e| Error::io("write", HINTS_PATH, e))
+    }
+
+    // Take over the hints revealed elsewhere, like on another machine
+    pub fn merge(&mut self, other: &RevealedHints) {
+        for (name, &level) in &other.levels {
+            let known = self.levels.entry(name.clone()).or_default();
+            *known = level.max(*known);
+        }
+    }
+
+    pub fn used(&self, exercise: &Exercise) -> usize {
+        self.levels.get(&exercise.name).copied().unwrap_or(0)
+    }
+
+    // Reveal the next hint level of the exercise and return every hint
+    // revealed so far. Once all of them are revealed, this stays the same.
+    pub fn reveal_next<'a>(&mut self, exercise: &'a Exercise) -> Vec<&'a str> {
+        let hints = exercise.hint_levels();
+        let level = (self.used(exercise) + 1).min(hints.len());
+        self.levels.insert(exercise.name.clone(), level);
+        hints[..level].to_vec()
+    }
+}
@@ -0,0 +1,265 @@
+use rustlings::exercise::DEBUG_DIR;
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// The editors rustlings writes task and debugger configurations for
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ide {
+    VsCode,
+    Helix,
+    Nvim,
+}
+
+impl FromStr for Ide {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "vscode" | "code" => Ok(Ide::VsCode),
+            "helix" | "hx" => Ok(Ide::Helix),
+            "nvim" | "neovim" => Ok(Ide::Nvim),
+            _ => Err(format!(
+                "unknown editor '{s}', expected one of: vscode, helix, nvim"
+            )),
+        }
+    }
+}
+
+// Reads rustc errors and failing tests from `rustlings run` and
+// `rustlings build`, which print plain diagnostics with NO_COLOR set
+fn vscode_problem_matchers() -> serde_json::Value {
+    json!([
+        {
+            "owner": "rustlings",
+            "fileLocation": ["relative", "${workspaceFolder}"],
+            "pattern": [
+                {
+                    "regexp": "^(warning|error)(?:\\[(\\w+)\\])?: (.*)$",
+                    "severity": 1,
+                    "code": 2,
+                    "message": 3
+                },
+                {
+                    "regexp": "^\\s*--> (.*):(\\d+):(\\d+)$",
+                    "file": 1,
+                    "line": 2,
+                    "column": 3
+                }
+            ]
+        },
+        {
+            "owner": "rustlings",
+            "fileLocation": ["relative", "${workspaceFolder}"],
+            "severity": "error",
+            "pattern": {
+                "regexp": "^thread '(.*)' panicked at (.*\\.rs):(\\d+):(\\d+):?$",
+                "message": 1,
+                "file": 2,
+                "line": 3,
+                "column": 4
+            }
+        }
+    ])
+}
+
+fn vscode_task(label: &str, args: &[&str]) -> serde_json::Value {
+    json!({
+        "label": label,
+        "type": "process",
+        "command": "rustlings",
+        "args": args,
+        "options": { "env": { "NO_COLOR": "1" } },
+        "presentation": { "reveal": "always", "clear": true },
+        "problemMatcher": vscode_problem_matchers()
+    })
+}
+
+const VSCODE_BUILD_TASK: &str = "rustlings: build current exercise for debugging";
+
+fn vscode_files() -> Vec<(PathBuf, String)> {
+    let mut run = vscode_task("rustlings: run current exercise", &["run", "${file}"]);
+    run["group"] = json!({ "kind": "build", "isDefault": true });
+    let tasks = json!({
+        "version": "2.0.0",
+        "tasks": [
+            run,
+            vscode_task("rustlings: hint for current exercise", &["hint", "${file}"]),
+            vscode_task(VSCODE_BUILD_TASK, &["build", "${file}"]),
+        ]
+    });
+    let program = format!("${{workspaceFolder}}/{DEBUG_DIR}/${{fileBasenameNoExtension}}");
+    let launch = json!({
+        "version": "0.2.0",
+        "configurations": [
+            {
+                "name": "rustlings: debug current exercise (lldb)",
+                "type": "lldb",
+                "request": "launch",
+                "program": program,
+                "args": [],
+                "cwd": "${workspaceFolder}",
+                "sourceLanguages": ["rust"],
+                "preLaunchTask": VSCODE_BUILD_TASK
+            },
+            {
+                "name": "rustlings: debug current exercise (gdb)",
+                "type": "cppdbg",
+                "request": "launch",
+                "program": program,
+                "args": [],
+                "cwd": "${workspaceFolder}",
+                "MIMode": "gdb",
+                "miDebuggerPath": "rust-gdb",
+                "preLaunchTask": VSCODE_BUILD_TASK
+            }
+        ]
+    });
+    vec![
+        (PathBuf::from(".vscode/tasks.json"), pretty(&tasks)),
+        (PathBuf::from(".vscode/launch.json"), pretty(&launch)),
+    ]
+}
+
+fn pretty(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap() + "\n"
+}
+
+fn helix_files() -> Vec<(PathBuf, String)> {
+    let languages = format!(
+        r#"# Written by `rustlings ide helix`
+[[language]]
+name = "rust"
+
+[language.debugger]
+name = "lldb-dap"
+transport = "stdio"
+command = "lldb-dap"
+
+# Run `rustlings build <exercise>` first, or press F6 in the exercise
+[[language.debugger.templates]]
+name = "rustlings exercise"
+request = "launch"
+completion = [{{ name = "binary", completion = "filename", default = "{DEBUG_DIR}/" }}]
+args = {{ program = "{{0}}", cwd = "." }}
+"#
+    );
+    let config = r#"# Written by `rustlings ide helix`
+[keys.normal]
+F5 = ":sh NO_COLOR=1 rustlings run %{buffer_name}"
+F6 = ":sh NO_COLOR=1 rustlings build %{buffer_name}"
+"#;
+    vec![
+        (PathBuf::from(".helix/languages.toml"), languages),
+        (PathBuf::from(".helix/config.toml"), config.to_string()),
+    ]
+}
+
+fn nvim_files() -> Vec<(PathBuf, String)> {
+    let config = format!(
+        r#"-- Written by `rustlings ide nvim`, loaded with `:set exrc`
+-- `:make` runs the current exercise and fills the quickfix list with its
+-- compiler errors and failing tests
+vim.opt.makeprg = "NO_COLOR=1 rustlings run %"
+vim.opt.errorformat = {{
+  "%Eerror%m",
+  "%Wwarning%m",
+  "%C%*\\s--> %f:%l:%c",
+  "%Ethread '%m' panicked at %f:%l:%c:",
+  "%-G%.%#",
+}}
+
+vim.api.nvim_create_user_command("RustlingsHint", "!rustlings hint %", {{}})
+
+-- Builds the current exercise with debug info and returns the binary
+local function rustlings_build()
+  local file = vim.fn.expand("%")
+  local output = vim.fn.system({{ "rustlings", "build", file }})
+  if vim.v.shell_error ~= 0 then
+    error(output)
+  end
+  return vim.fn.getcwd() .. "/{DEBUG_DIR}/" .. vim.fn.expand("%:t:r")
+end
+
+vim.api.nvim_create_user_command("RustlingsBuild", rustlings_build, {{}})
+
+local ok, dap = pcall(require, "dap")
+if ok then
+  dap.configurations.rust = {{
+    {{
+      name = "rustlings: debug current exercise (lldb)",
+      type = "lldb",
+      request = "launch",
+      program = rustlings_build,
+      cwd = "${{workspaceFolder}}",
+    }},
+    {{
+      name = "rustlings: debug current exercise (gdb)",
+      type = "gdb",
+      request = "launch",
+      program = rustlings_build,
+      cwd = "${{workspaceFolder}}",
+    }},
+  }}
+end
+"#
+    );
+    vec![(PathBuf::from(".nvim.lua"), config)]
+}
+
+// Write the task and debugger configurations for the editor. Existing files
+// are kept unless `force` is given. Returns the files that were written.
+pub fn write_config(ide: Ide, force: bool) -> Result<Vec<PathBuf>, String> {
+    let files = match ide {
+        Ide::VsCode => vscode_files(),
+        Ide::Helix => helix_files(),
+        Ide::Nvim => nvim_files(),
+    };
+    if !force {
+        if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+            return Err(format!(
+                "{} already exists, pass `--force` to replace it",
+                path.display()
+            ));
+        }
+    }
+    for (path, contents) in &files {
+        if let Some(dir) = path.parent().filter(|dir| dir != &Path::new("")) {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Could not create {}: {e}", dir.display()))?;
+        }
+        fs::write(path, contents)
+            .map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_problem_matchers_read_rustlings_output() {
+        let matchers = vscode_problem_matchers();
+        let error = Regex::new(matchers[0]["pattern"][0]["regexp"].as_str().unwrap()).unwrap();
+        let span = Regex::new(matchers[0]["pattern"][1]["regexp"].as_str().unwrap()).unwrap();
+        let panic = Regex::new(matchers[1]["pattern"]["regexp"].as_str().unwrap()).unwrap();
+
+        let captures = error
+            .captures("error[E0425]: cannot find value `x` in this scope")
+            .unwrap();
+        assert_eq!(&captures[2], "E0425");
+        let captures = span
+            .captures("  --> exercises/intro/intro2.rs:8:21")
+            .unwrap();
+        assert_eq!(&captures[1], "exercises/intro/intro2.rs");
+        assert_eq!(&captures[2], "8");
+        let captures = panic
+            .captures("thread 'tests::it_works' panicked at exercises/tests/tests1.rs:19:9:")
+            .unwrap();
+        assert_eq!(&captures[2], "exercises/tests/tests1.rs");
+        assert_eq!(&captures[3], "19");
+    }
+}
@@ -0,0 +1,21 @@
+//! The exercises of a rustlings course and everything needed to check them:
+//! loading `info.toml`, compiling and running exercises, verifying a course
+//! and grading it. The `rustlings` command line tool is built on top of it.
+//! Nothing in here prints, results are returned for the caller to show.
+
+pub mod doctest;
+pub mod error;
+pub mod evaluate;
+pub mod exercise;
+pub mod grade;
+pub mod hints;
+pub mod pristine;
+pub mod project;
+pub mod report;
+pub mod runner;
+
+pub use crate::error::Error;
+pub use crate::evaluate::{evaluate, Outcome};
+pub use crate::exercise::{Exercise, ExerciseList, ExerciseOutput, RunInput, State};
+pub use crate::grade::{grade, ExerciseCheckList};
+pub use crate::runner::{register, Check, ExerciseRunner, InvalidSetting, LspConfig};
@@ -1,31 +1,52 @@
-use crate::exercise::{Exercise, ExerciseList};
-use crate::project::RustAnalyzerProject;
-use crate::run::{reset, run};
-use crate::verify::verify;
+use crate::archive::{restore, save};
+use crate::bundle::{grade_bundle, submit};
+use crate::check::check_config;
+use crate::debug::{debug, Debugger};
+use crate::diff::{print_diff, print_origin, print_summary};
+use crate::doctor::doctor;
+use crate::editor::{failure_location, open_editor};
+use crate::ide::{write_config, Ide};
+use crate::run::run_with;
+use crate::solution::print_solution;
+use crate::tui::watch_tui;
+use crate::verify::{verify, verify_all};
+use crate::watch::{watch, WatchConfig, WatchStatus};
 use argh::FromArgs;
 use console::Emoji;
-use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use serde::{Deserialize, Serialize};
-use std::ffi::OsStr;
+use rustlings::exercise::{
+    estimated_minutes_remaining, format_minutes, Difficulty, Exercise, ExerciseList, RunInput,
+};
+use rustlings::grade::{grade, ExerciseCheckList};
+use rustlings::hints::RevealedHints;
+use rustlings::pristine::{reset, snapshot, undo_reset, PRISTINE_DIR};
+use rustlings::project::RustAnalyzerProject;
+use rustlings::Error;
 use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
-use std::time::{UNIX_EPOCH, SystemTime};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
 
+// The output macros of the subcommands, which the library never prints with
 #[macro_use]
 mod ui;
 
-mod exercise;
-mod project;
+mod archive;
+mod bundle;
+mod check;
+mod debug;
+mod diagnostics;
+mod diff;
+mod doctor;
+mod editor;
+mod ide;
 mod run;
+mod solution;
+mod tui;
 mod verify;
+mod watch;
 
 // In sync with crate version
 const VERSION: &str = "5.5.1";
@@ -39,6 +60,12 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// use another course description than "info.toml"
+    #[argh(option, default = "PathBuf::from(\"info.toml\")")]
+    info: PathBuf,
+    /// only use the exercises of the given course profile
+    #[argh(option)]
+    profile: Option<String>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -49,21 +76,71 @@ enum Subcommands {
     Verify(VerifyArgs),
     Watch(WatchArgs),
     Run(RunArgs),
+    Build(BuildArgs),
+    Debug(DebugArgs),
     Reset(ResetArgs),
+    Diff(DiffArgs),
     Hint(HintArgs),
+    Solution(SolutionArgs),
+    Save(SaveArgs),
+    Restore(RestoreArgs),
     List(ListArgs),
     Lsp(LspArgs),
-    CicvVerify(CicvVerifyArgs)
+    Ide(IdeArgs),
+    CheckConfig(CheckConfigArgs),
+    Snapshot(SnapshotArgs),
+    Doctor(DoctorArgs),
+    CicvVerify(CicvVerifyArgs),
+    Submit(SubmitArgs),
+    GradeBundle(GradeBundleArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "cicvverify", description = "cicvverify")]
-struct CicvVerifyArgs {}
+struct CicvVerifyArgs {
+    #[argh(option)]
+    /// the share of an exercise's score lost per revealed hint, like 0.1
+    hint_penalty: Option<f32>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "submit")]
+/// Grades your work and writes it to a bundle for offline submission
+struct SubmitArgs {
+    #[argh(option)]
+    /// the directory to write the bundle to
+    out: PathBuf,
+    #[argh(option)]
+    /// the share of an exercise's score lost per revealed hint, like 0.1
+    hint_penalty: Option<f32>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "grade-bundle")]
+/// Grades a submitted bundle again and reports where it differs from the submitted results
+struct GradeBundleArgs {
+    #[argh(positional)]
+    /// the directory written by `submit`
+    bundle: PathBuf,
+    #[argh(option)]
+    /// the share of an exercise's score lost per revealed hint, like 0.1
+    hint_penalty: Option<f32>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    /// open your editor at the exercise that needs work
+    #[argh(switch)]
+    open: bool,
+    /// verify every exercise and print a summary per track
+    #[argh(switch)]
+    keep_going: bool,
+    /// how many exercises to verify at a time, implies --keep-going
+    #[argh(option)]
+    jobs: Option<usize>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
@@ -72,6 +149,15 @@ struct WatchArgs {
     /// show hints on success
     #[argh(switch)]
     success_hints: bool,
+    /// use a full-screen terminal interface
+    #[argh(switch)]
+    tui: bool,
+    /// poll for changes instead of relying on file system notifications
+    #[argh(switch)]
+    poll: bool,
+    /// names or glob patterns of the only exercises to watch
+    #[argh(positional)]
+    exercises: Vec<String>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -79,26 +165,110 @@ struct WatchArgs {
 /// Runs/Tests a single exercise
 struct RunArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the name or path of the exercise
+    name: String,
+    #[argh(positional)]
+    /// after `--`: test filters and flags like `--exact`, or program arguments
+    args: Vec<String>,
+    /// a file to read the program's standard input from
+    #[argh(option)]
+    stdin: Option<PathBuf>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "build")]
+/// Compiles an exercise with debug info to target/rustlings/<name>
+struct BuildArgs {
+    #[argh(positional)]
+    /// the name or path of the exercise
     name: String,
 }
 
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "debug")]
+/// Builds an exercise with debug info and starts rust-gdb or rust-lldb on it
+struct DebugArgs {
+    #[argh(positional)]
+    /// the name or path of the exercise
+    name: String,
+    #[argh(positional)]
+    /// only run the tests whose name contains this
+    filter: Option<String>,
+    /// the debugger to use: gdb or lldb, whichever is installed by default
+    #[argh(option)]
+    debugger: Option<Debugger>,
+}
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Resets a single exercise to its original state
 struct ResetArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the name or path of the exercise
     name: String,
+    /// don't ask for confirmation
+    #[argh(switch, short = 'y')]
+    yes: bool,
+    /// bring back your version from before the last reset
+    #[argh(switch)]
+    undo: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "diff")]
+/// Shows what you changed in an exercise since its original version
+struct DiffArgs {
+    #[argh(positional)]
+    /// the name or path of the exercise
+    name: Option<String>,
+    /// only show changes to the `#[cfg(test)]` modules
+    #[argh(switch)]
+    tests_only: bool,
+    /// summarize the changed lines of every exercise instead
+    #[argh(switch)]
+    all: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "hint")]
-/// Returns a hint for the given exercise
+/// Reveals the next hint for the given exercise
 struct HintArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the name or path of the exercise
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "solution")]
+/// Shows the reference solution of an exercise you have solved
+struct SolutionArgs {
+    #[argh(positional)]
+    /// the name or path of the exercise
     name: String,
+    /// show how your version differs from the solution
+    #[argh(switch)]
+    diff: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "save")]
+/// Saves your changed exercises and hints to a file, to continue elsewhere
+struct SaveArgs {
+    #[argh(positional)]
+    /// the archive to write
+    file: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "restore")]
+/// Restores your work from a file written by `save`
+struct RestoreArgs {
+    #[argh(positional)]
+    /// the archive to read
+    file: PathBuf,
+    /// also restore exercises that changed upstream since they were saved
+    #[argh(switch)]
+    force: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -106,6 +276,41 @@ struct HintArgs {
 /// Enable rust-analyzer for exercises
 struct LspArgs {}
 
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "ide")]
+/// Writes tasks and debugger configurations for vscode, helix or nvim
+struct IdeArgs {
+    #[argh(positional)]
+    /// the editor: vscode, helix or nvim
+    editor: Ide,
+    /// replace configuration files that already exist
+    #[argh(switch)]
+    force: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-config")]
+/// Checks info.toml for mistakes, exits with 1 if there are any
+struct CheckConfigArgs {
+    #[argh(switch)]
+    /// also check that every reference solution in solutions/ passes
+    solutions: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "snapshot")]
+/// Stores the exercises as the originals `reset` and `diff` use, for course authors
+struct SnapshotArgs {
+    #[argh(switch)]
+    /// replace the originals the course already has
+    force: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "doctor")]
+/// Checks that everything rustlings needs is installed and set up
+struct DoctorArgs {}
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "list")]
 /// Lists the exercises available in Rustlings
@@ -126,27 +331,38 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
+    #[argh(option, short = 't')]
+    /// display only exercises with the given tag or track
+    /// comma separated tags are acceptable
+    tag: Option<String>,
+    #[argh(option, short = 'd')]
+    /// display only exercises of the given difficulty (easy, medium or hard)
+    difficulty: Option<Difficulty>,
+    #[argh(option)]
+    /// sort the exercises by "name", "difficulty" or "time" instead of the recommended order
+    sort: Option<SortKey>,
 }
 
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseCheckList {
-    pub exercises: Vec<ExerciseResult>,
-    pub user_name: Option<String>,
-    pub statistics: ExerciseStatistics
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseResult {
-    pub name: String,
-    pub result: bool
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum SortKey {
+    Name,
+    Difficulty,
+    Time,
 }
 
-#[derive(Deserialize, Serialize)]
-pub struct  ExerciseStatistics {
-    pub total_exercations: usize,
-    pub total_succeeds: usize,
-    pub total_failures: usize,
-    pub total_time: u32,
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(SortKey::Name),
+            "difficulty" => Ok(SortKey::Difficulty),
+            "time" => Ok(SortKey::Time),
+            _ => Err(format!(
+                "unknown sort key '{s}', expected one of: name, difficulty, time"
+            )),
+        }
+    }
 }
 
 #[tokio::main]
@@ -162,7 +378,13 @@ async fn main() {
         println!("\n{WELCOME}\n");
     }
 
-    if !Path::new("info.toml").exists() {
+    // The doctor has to work when nothing else does
+    if let Some(Subcommands::Doctor(_)) = &args.nested {
+        let ok = doctor(&args.info);
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+
+    if !args.info.exists() {
         println!(
             "{} must be run from the rustlings directory",
             std::env::current_exe().unwrap().to_str().unwrap()
@@ -178,8 +400,18 @@ async fn main() {
         std::process::exit(1);
     }
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    // Checking the configuration has to work even if it can't be loaded
+    if let Some(Subcommands::CheckConfig(subargs)) = &args.nested {
+        let ok = check_config(&args.info, subargs.solutions);
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+
+    let exercise_list = ExerciseList::load_profile(&args.info, args.profile.as_deref())
+        .unwrap_or_else(|e| {
+            println!("{e}");
+            std::process::exit(1);
+        });
+    let exercises = exercise_list.exercises;
     let verbose = args.nocapture;
 
     let command = args.nested.unwrap_or_else(|| {
@@ -189,50 +421,83 @@ async fn main() {
     match command {
         Subcommands::List(subargs) => {
             if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+                println!(
+                    "{:<17}\t{:<46}\t{:<7}\t{:<10}\tTime",
+                    "Name", "Path", "Status", "Difficulty"
+                );
             }
             let mut exercises_done: u16 = 0;
             let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
-            exercises.iter().for_each(|e| {
+            let tags = subargs.tag.clone().unwrap_or_default();
+            let mut listed: Vec<&Exercise> = exercises
+                .iter()
+                .filter(|e| {
+                    let fname = format!("{}", e.path.display());
+                    let filter_cond = filters
+                        .split(',')
+                        .filter(|f| !f.trim().is_empty())
+                        .any(|f| e.name.contains(f) || fname.contains(f));
+                    let tag_cond = tags
+                        .split(',')
+                        .filter(|t| !t.trim().is_empty())
+                        .any(|t| e.has_tag(t));
+                    let difficulty_cond =
+                        subargs.difficulty.is_none_or(|d| e.difficulty == Some(d));
+                    let done = e.looks_done();
+                    if done {
+                        exercises_done += 1;
+                    }
+                    let solve_cond = {
+                        (done && subargs.solved)
+                            || (!done && subargs.unsolved)
+                            || (!subargs.solved && !subargs.unsolved)
+                    };
+                    solve_cond
+                        && (filter_cond || subargs.filter.is_none())
+                        && (tag_cond || subargs.tag.is_none())
+                        && difficulty_cond
+                })
+                .collect();
+            match subargs.sort {
+                Some(SortKey::Name) => listed.sort_by(|a, b| a.name.cmp(&b.name)),
+                // Unrated exercises go last, `sort_by_key` keeps the course order otherwise
+                Some(SortKey::Difficulty) => {
+                    listed.sort_by_key(|e| (e.difficulty.is_none(), e.difficulty))
+                }
+                Some(SortKey::Time) => {
+                    listed.sort_by_key(|e| (e.estimated_minutes.is_none(), e.estimated_minutes))
+                }
+                None => {}
+            }
+            for e in &listed {
                 let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
-                    exercises_done += 1;
-                    "Done"
+                let line = if subargs.paths {
+                    format!("{fname}\n")
+                } else if subargs.names {
+                    format!("{}\n", e.name)
                 } else {
-                    "Pending"
-                };
-                let solve_cond = {
-                    (e.looks_done() && subargs.solved)
-                        || (!e.looks_done() && subargs.unsolved)
-                        || (!subargs.solved && !subargs.unsolved)
+                    let status = if e.looks_done() { "Done" } else { "Pending" };
+                    let difficulty = e.difficulty.map_or("-".to_string(), |d| d.to_string());
+                    let time = e.estimated_minutes.map_or("-".to_string(), format_minutes);
+                    format!(
+                        "{:<17}\t{fname:<46}\t{status:<7}\t{difficulty:<10}\t{time}\n",
+                        e.name
+                    )
                 };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
-                    let line = if subargs.paths {
-                        format!("{fname}\n")
-                    } else if subargs.names {
-                        format!("{}\n", e.name)
-                    } else {
-                        format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name)
-                    };
-                    // Somehow using println! leads to the binary panicking
-                    // when its output is piped.
-                    // So, we're handling a Broken Pipe error and exiting with 0 anyway
-                    let stdout = std::io::stdout();
-                    {
-                        let mut handle = stdout.lock();
-                        handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
-                            match e.kind() {
-                                std::io::ErrorKind::BrokenPipe => std::process::exit(0),
-                                _ => std::process::exit(1),
-                            };
-                        });
-                    }
+                // Somehow using println! leads to the binary panicking
+                // when its output is piped.
+                // So, we're handling a Broken Pipe error and exiting with 0 anyway
+                let stdout = std::io::stdout();
+                {
+                    let mut handle = stdout.lock();
+                    handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
+                        match e.kind() {
+                            std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+                            _ => std::process::exit(1),
+                        };
+                    });
                 }
-            });
+            }
             let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
             println!(
                 "Progress: You completed {} / {} exercises ({:.1} %).",
@@ -240,106 +505,209 @@ async fn main() {
                 exercises.len(),
                 percentage_progress
             );
+            print_time_remaining(&exercises);
             std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            let input = RunInput {
+                args: subargs.args.clone(),
+                stdin: subargs.stdin.clone(),
+            };
+            run_with(exercise, &input, verbose).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Build(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+            match exercise.compile_debug() {
+                Ok(path) => success!("Built {} for debugging", path.display()),
+                Err(Error::Compile(output)) => {
+                    warn!(
+                        "Compilation of {} failed!, Compiler error message:\n",
+                        exercise
+                    );
+                    println!("{}", output.stderr);
+                    std::process::exit(1);
+                }
+                Err(error) => {
+                    println!("{error}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::Debug(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+            match debug(exercise, subargs.filter.as_deref(), subargs.debugger) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    println!("{e}");
+                    std::process::exit(1);
+                }
+            }
         }
 
         Subcommands::Reset(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
 
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            if subargs.undo {
+                if let Err(e) = undo_reset(exercise) {
+                    println!("Could not undo the reset: {e}");
+                    std::process::exit(1);
+                }
+                success!("Brought back your version of {}", exercise);
+            } else {
+                let question = format!("Reset {exercise} to its original state?");
+                if !subargs.yes && !confirm(&question) {
+                    println!("Nothing was reset.");
+                    std::process::exit(1);
+                }
+                if let Err(e) = reset(exercise) {
+                    println!("Could not reset {exercise}: {e}");
+                    std::process::exit(1);
+                }
+                success!("Reset {} to its original state", exercise);
+                println!(
+                    "Your version was kept, `rustlings reset --undo {}` brings it back.",
+                    exercise.name
+                );
+            }
         }
 
+        Subcommands::Diff(subargs) => match (&subargs.name, subargs.all) {
+            (None, true) => print_summary(&exercises, subargs.tests_only),
+            (Some(name), false) => {
+                let exercise = find_exercise(name, &exercises);
+                match print_diff(exercise, subargs.tests_only) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        println!("{exercise} is unchanged.");
+                        print_origin();
+                    }
+                    Err(e) => {
+                        println!("Could not compare {exercise} with its original: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            _ => {
+                println!("Give either the name of an exercise or --all.");
+                std::process::exit(1);
+            }
+        },
+
         Subcommands::Hint(subargs) => {
             let exercise = find_exercise(&subargs.name, &exercises);
 
-            println!("{}", exercise.hint);
+            print_next_hint(exercise);
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose, false)
-                .unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::Solution(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises);
+
+            if let Err(e) = print_solution(exercise, subargs.diff) {
+                println!("{e}");
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Save(subargs) => {
+            if let Err(e) = save(&subargs.file, &exercises) {
+                println!("{e}");
+                std::process::exit(1);
+            }
         }
 
-        Subcommands::CicvVerify(_subargs) => {
-            // let toml_str = &fs::read_to_string("info.toml").unwrap();
-            // exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-            let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let rights = Arc::new(Mutex::new(0));
-            let alls = exercises.len();
-
-            let exercise_check_list =  Arc::new(Mutex::new(
-                ExerciseCheckList {
-                    exercises: vec![], 
-                    user_name:  None, 
-                    statistics: ExerciseStatistics { 
-                        total_exercations: alls, 
-                        total_succeeds: 0, 
-                        total_failures: 0, 
-                        total_time: 0, 
+        Subcommands::Restore(subargs) => match restore(&subargs.file, &exercises, subargs.force) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                println!("{e}");
+                std::process::exit(1);
+            }
+        },
+
+        Subcommands::Verify(subargs) if subargs.keep_going || subargs.jobs.is_some() => {
+            if !verify_all(&exercises, subargs.jobs.unwrap_or(1)) {
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Verify(subargs) => {
+            if let Err(failure) = verify(&exercises, (0, exercises.len()), verbose, false, None) {
+                if subargs.open {
+                    let location = failure_location(failure.exercise, failure.output.as_ref());
+                    if let Err(e) = open_editor(&location) {
+                        println!("Could not open your editor: {e}");
                     }
                 }
-            ));
-
-            let mut tasks = vec![];
-            for exercise in exercises {
-                let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                let inner_exercise = exercise;
-                let c_mutex = Arc::clone(&rights);
-                let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-                let _verbose = verbose.clone();
-                let t = tokio::task::spawn( async move {
-                    match run(&inner_exercise, true) {
-                    // match verify(vec![&inner_exercise], (0, 1), true, true) {
-                        Ok(_) => {
-                            *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: true,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_succeeds += 1;
-                        },
-                        Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: false,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_failures += 1;
-                        }
-                    }
-                });
-                tasks.push(t);
+                std::process::exit(1);
             }
-            for task in tasks { task.await.unwrap(); }
-            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let total_time = now_end - now_start;
-            println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time);
-            let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-            exercise_check_list_ref.lock().unwrap().statistics.total_time = total_time as u32;
-            let serialized = serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
+        }
+
+        Subcommands::CicvVerify(subargs) => {
+            let revealed_hints = RevealedHints::load();
+            let hint_penalty = subargs.hint_penalty.unwrap_or(0.0);
+            let exercise_check_list = grade_course(exercises, &revealed_hints, hint_penalty).await;
+            let serialized = serde_json::to_string_pretty(&exercise_check_list).unwrap();
             fs::write(".github/result/check_result.json", serialized).unwrap();
-        },
+        }
+
+        Subcommands::Submit(subargs) => {
+            let hint_penalty = subargs.hint_penalty.unwrap_or(0.0);
+            let course = args.info.parent().unwrap_or_else(|| Path::new(""));
+            if let Err(e) = submit(&subargs.out, course, exercises, hint_penalty).await {
+                println!("{e}");
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::GradeBundle(subargs) => {
+            let hint_penalty = subargs.hint_penalty.unwrap_or(0.0);
+            let course = args.info.parent().unwrap_or_else(|| Path::new(""));
+            match grade_bundle(&subargs.bundle, course, &exercises, hint_penalty).await {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    println!("{e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::CheckConfig(_subargs) => unreachable!("handled before loading info.toml"),
+
+        Subcommands::Snapshot(subargs) => {
+            if Path::new(PRISTINE_DIR).exists() && !subargs.force {
+                println!("The course already has originals in {PRISTINE_DIR}, pass --force to replace them.");
+                std::process::exit(1);
+            }
+            if let Err(e) = snapshot(&exercises) {
+                println!("Could not store the originals: {e}");
+                std::process::exit(1);
+            }
+            let count = exercises.len();
+            success!("Stored the originals of {count} exercises in {PRISTINE_DIR}");
+            println!("Commit them with the course, so that learners can reset exercises.");
+        }
+
+        Subcommands::Doctor(_subargs) => unreachable!("handled before loading info.toml"),
 
         Subcommands::Lsp(_subargs) => {
             let mut project = RustAnalyzerProject::new();
-            project
-                .get_sysroot_src()
-                .expect("Couldn't find toolchain path, do you have `rustc` installed?");
-            project
-                .exercises_to_json()
-                .expect("Couldn't parse rustlings exercises files");
+            if let Err(e) = project.get_sysroot_src() {
+                println!("Couldn't find the toolchain path: {e}");
+                std::process::exit(1);
+            }
+            if std::env::var("RUST_SRC_PATH").is_err() {
+                println!(
+                    "Determined the standard library sources: {}\n",
+                    project.sysroot_src
+                );
+            }
+            project.exercises_to_json(&exercises);
 
             if project.crates.is_empty() {
                 println!("Failed find any exercises, make sure you're in the `rustlings` folder");
@@ -351,74 +719,77 @@ async fn main() {
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
+        Subcommands::Ide(subargs) => match write_config(subargs.editor, subargs.force) {
+            Ok(files) => {
+                for file in files {
+                    success!("Wrote {}", file.display());
+                }
+            }
             Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+                println!("{e}");
                 std::process::exit(1);
             }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
-            }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
-            }
         },
-    }
-}
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
-    thread::spawn(move || loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
-                    }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else if let Some(cmd) = input.strip_prefix('!') {
-                    let parts: Vec<&str> = cmd.split_whitespace().collect();
-                    if parts.is_empty() {
-                        println!("no command provided");
-                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
-                        println!("failed to execute command `{}`: {}", cmd, e);
-                    }
-                } else {
-                    println!("unknown command: {input}");
+        Subcommands::Watch(subargs) => {
+            let config = WatchConfig {
+                info: &args.info,
+                profile: args.profile.as_deref(),
+                focus: &subargs.exercises,
+                poll: subargs.poll,
+            };
+            let list = config.load().unwrap_or_else(|e| {
+                println!("{e}");
+                std::process::exit(1);
+            });
+            // A focused session shows the hints of the few exercises it's about
+            let success_hints = subargs.success_hints || !subargs.exercises.is_empty();
+            let status = if subargs.tui {
+                watch_tui(&config, list, verbose, success_hints)
+            } else {
+                watch(&config, list, verbose, success_hints)
+            };
+            match status {
+                Err(e) => {
+                    println!(
+                        "Error: Could not watch your progress. Error message was {:?}.",
+                        e
+                    );
+                    println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+                    println!("If file system notifications don't work here, try `rustlings watch --poll`.");
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) if !subargs.exercises.is_empty() => {
+                    println!(
+                        "{emoji} All selected exercises are done! {emoji}",
+                        emoji = Emoji("🎉", "★")
+                    );
+                }
+                Ok(WatchStatus::Finished) => {
+                    println!(
+                        "{emoji} All exercises completed! {emoji}",
+                        emoji = Emoji("🎉", "★")
+                    );
+                    println!("\n{FENISH_LINE}\n");
+                }
+                Ok(WatchStatus::Unfinished) => {
+                    println!("We hope you're enjoying learning about Rust!");
+                    println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
                 }
             }
-            Err(error) => println!("error reading command: {error}"),
         }
-    });
+    }
+}
+
+// Ask a yes/no question, defaulting to no
+fn confirm(question: &str) -> bool {
+    print!("{question} [y/N] ");
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
 }
 
 fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
@@ -432,9 +803,11 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
                 std::process::exit(1)
             })
     } else {
+        // Editors pass the path of the file that is open
+        let path = fs::canonicalize(name).ok();
         exercises
             .iter()
-            .find(|e| e.name == name)
+            .find(|e| e.name == name || path.is_some() && fs::canonicalize(&e.path).ok() == path)
             .unwrap_or_else(|| {
                 println!("No exercise found for '{name}'!");
                 std::process::exit(1)
@@ -442,89 +815,80 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
     }
 }
 
-enum WatchStatus {
-    Finished,
-    Unfinished,
-}
-
-fn watch(
-    exercises: &[Exercise],
-    verbose: bool,
-    success_hints: bool,
-) -> notify::Result<WatchStatus> {
-    /* Clears the terminal with an ANSI escape code.
-    Works in UNIX and newer Windows terminals. */
-    fn clear_screen() {
-        println!("\x1Bc");
+// Reveal the next hint of the exercise, record it and print every hint
+// revealed so far. Single hints are printed as they are.
+fn print_next_hint(exercise: &Exercise) {
+    let mut revealed = RevealedHints::load();
+    let hints = revealed.reveal_next(exercise);
+    if revealed.save().is_err() {
+        warn!("Could not record the hints revealed for {}", exercise);
+    }
+    let total = exercise.hint_levels().len();
+    if total == 1 {
+        println!("{}", hints[0]);
+        return;
+    }
+    for (i, hint) in hints.iter().enumerate() {
+        println!("Hint {}/{total}: {hint}", i + 1);
+    }
+    if hints.len() == total {
+        println!("That was the last hint for this exercise.");
     }
+}
 
-    let (tx, rx) = channel();
-    let should_quit = Arc::new(AtomicBool::new(false));
-
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
-
-    clear_screen();
-
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
-        exercises.iter(),
-        (0, exercises.len()),
-        verbose,
-        success_hints,
-    ) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
-    };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
-    loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            },
-            Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
+// Grade the course like the CI grader does, printing every exercise's output
+// and how far grading got as the results come in
+async fn grade_course(
+    exercises: Vec<Exercise>,
+    revealed_hints: &RevealedHints,
+    hint_penalty: f32,
+) -> ExerciseCheckList {
+    let start = Instant::now();
+    let alls = exercises.len();
+    let rights = AtomicUsize::new(0);
+    let check_list = grade(
+        exercises,
+        revealed_hints,
+        hint_penalty,
+        move |exercise, output| {
+            match output {
+                Ok(output) => println!("{}", output.stdout),
+                Err(Error::Compile(output)) => println!("{}", output.stderr),
+                Err(Error::Run(output)) => println!("{}\n{}", output.stdout, output.stderr),
+                Err(error) => warn!("{}", error),
             }
-            Err(e) => println!("watch error: {e:?}"),
-        }
-        // Check if we need to exit
-        if should_quit.load(Ordering::SeqCst) {
-            return Ok(WatchStatus::Unfinished);
-        }
+            if output.is_ok() {
+                rights.fetch_add(1, Ordering::Relaxed);
+                println!("{}执行成功", exercise.name);
+            } else {
+                println!("{}执行失败", exercise.name);
+            }
+            println!("总的题目数: {}", alls);
+            println!("当前做正确的题目数: {}", rights.load(Ordering::Relaxed));
+            println!("当前修改试卷耗时: {} s", start.elapsed().as_secs());
+        },
+    )
+    .await;
+    println!(
+        "===============================试卷批改完成,总耗时: {} s; ==================================",
+        check_list.statistics.total_time
+    );
+    check_list
+}
+
+fn print_time_remaining(exercises: &[Exercise]) {
+    let minutes = estimated_minutes_remaining(exercises);
+    if minutes > 0 {
+        println!(
+            "Estimated time remaining: about {}.",
+            format_minutes(minutes)
+        );
     }
 }
 
 fn rustc_exists() -> bool {
     Command::new("rustc")
-        .args(&["--version"])
+        .args(["--version"])
         .stdout(Stdio::null())
         .spawn()
         .and_then(|mut child| child.wait())
@@ -0,0 +1,121 @@
+use crate::error::Error;
+use crate::exercise::Exercise;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+// The original copies of the exercises that a course ships, committed along
+// with it. Course authors write them with `snapshot`.
+pub const PRISTINE_DIR: &str = ".rustlings/pristine";
+// The learner's version of an exercise from before its last reset
+const BACKUP_DIR: &str = ".rustlings/backup";
+
+// Where the copy of an exercise lives inside one of the hidden stores
+fn store_path(store: &str, exercise: &Exercise) -> PathBuf {
+    let relative: PathBuf = exercise
+        .path
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+    Path::new(store).join(relative)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+// The exercise as it was first checked into git, which is how the course
+// handed it out. Later commits may well be the learner's own work.
+fn first_committed_source(exercise: &Exercise) -> Option<String> {
+    let dir = match exercise.path.parent() {
+        Some(dir) if dir != Path::new("") => dir,
+        _ => Path::new("."),
+    };
+    let file = exercise.path.file_name()?.to_str()?;
+    let commits = git(dir, &["log", "--diff-filter=A", "--format=%H", "--", file])?;
+    let first = commits.lines().last()?;
+    git(dir, &["show", &format!("{first}:./{file}")])
+}
+
+// Whether the course ships the originals of its exercises, rather than
+// leaving them to git
+pub fn has_snapshot() -> bool {
+    Path::new(PRISTINE_DIR).is_dir()
+}
+
+// The original version of the exercise: the copy in the snapshot the course
+// ships, or for a course without one, the version git first saw
+pub fn pristine_source(exercise: &Exercise) -> Result<String, Error> {
+    if !has_snapshot() {
+        return first_committed_source(exercise).ok_or_else(|| {
+            Error::Config(format!("there is no original copy of {exercise}, the course has no snapshot in {PRISTINE_DIR} and git doesn't know it"))
+        });
+    }
+    let original = store_path(PRISTINE_DIR, exercise);
+    fs::read_to_string(&original).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => Error::Config(format!(
+            "the snapshot in {PRISTINE_DIR} has no original copy of {exercise}"
+        )),
+        _ => Error::io("read", original, e),
+    })
+}
+
+// Store the exercises as they are now as the originals of the course, for
+// course authors to commit along with it. An exercise without its
+// `I AM NOT DONE` marker has been worked on and is refused.
+pub fn snapshot(exercises: &[Exercise]) -> Result<(), Error> {
+    if let Some(done) = exercises.iter().find(|exercise| exercise.looks_done()) {
+        return Err(Error::Input(format!(
+            "{done} has no `I AM NOT DONE` marker, so it isn't an original exercise"
+        )));
+    }
+    for exercise in exercises {
+        copy(&exercise.path, &store_path(PRISTINE_DIR, exercise))?;
+    }
+    Ok(())
+}
+
+fn copy(from: &Path, to: &Path) -> Result<(), Error> {
+    if let Some(dir) = to.parent() {
+        fs::create_dir_all(dir).map_err(|e| Error::io("create", dir, e))?;
+    }
+    fs::copy(from, to).map_err(|e| Error::io("copy", from, e))?;
+    Ok(())
+}
+
+// Put the original version of the exercise back in place, keeping the
+// learner's version as a backup
+pub fn reset(exercise: &Exercise) -> Result<(), Error> {
+    let original = pristine_source(exercise)?;
+    back_up(exercise)?;
+    fs::write(&exercise.path, original).map_err(|e| Error::io("write", &exercise.path, e))
+}
+
+// Keep the learner's version of the exercise before it gets replaced, so
+// that `undo_reset` can bring it back
+pub fn back_up(exercise: &Exercise) -> Result<(), Error> {
+    if exercise.path.exists() {
+        copy(&exercise.path, &store_path(BACKUP_DIR, exercise))?;
+    }
+    Ok(())
+}
+
+// Bring back the learner's version from before the last reset
+pub fn undo_reset(exercise: &Exercise) -> Result<(), Error> {
+    let backup = store_path(BACKUP_DIR, exercise);
+    if !backup.exists() {
+        return Err(Error::Input(format!("{exercise} hasn't been reset")));
+    }
+    copy(&backup, &exercise.path)?;
+    fs::remove_file(&backup).map_err(|e| Error::io("remove", backup, e))
+}
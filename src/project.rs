@@ -1,24 +1,81 @@
-use glob::glob;
+use crate::error::Error;
+use crate::exercise::Exercise;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
-use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Contains the structure of resulting rust-project.json file
 /// and functions to build the data required to create the file
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct RustAnalyzerProject {
-    sysroot_src: String,
+    pub sysroot_src: String,
     pub crates: Vec<Crate>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Crate {
+    display_name: String,
     root_module: String,
     edition: String,
     deps: Vec<String>,
     cfg: Vec<String>,
+    env: BTreeMap<String, String>,
+    // The Cargo manifest rustlings writes for exercises built with cargo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    build: Option<Build>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Build {
+    label: String,
+    build_file: String,
+    target_kind: String,
+}
+
+// What a build script passes on to the crate it builds
+#[derive(Default, Debug, PartialEq)]
+pub(crate) struct BuildScriptOutput {
+    pub env: BTreeMap<String, String>,
+    pub cfg: Vec<String>,
+}
+
+// The values of the `cargo:KEY=...` instructions in the source of a build
+// script. Only the literal part of the string is known, anything after a
+// format placeholder is left out.
+fn instructions(source: &str, key: &str) -> Vec<String> {
+    let needle = format!("{key}=");
+    source
+        .match_indices(&needle)
+        .map(|(i, _)| {
+            let rest = &source[i + needle.len()..];
+            let mut value = String::new();
+            let mut chars = rest.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next()),
+                    '"' | '{' | '\n' => break,
+                    c => value.push(c),
+                }
+            }
+            value
+        })
+        .collect()
+}
+
+pub(crate) fn scan_build_script(source: &str) -> BuildScriptOutput {
+    let env = instructions(source, "rustc-env")
+        .into_iter()
+        .filter_map(|variable| {
+            let (name, value) = variable.split_once('=')?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect();
+    BuildScriptOutput {
+        env,
+        cfg: instructions(source, "rustc-cfg"),
+    }
 }
 
 impl RustAnalyzerProject {
@@ -30,70 +87,110 @@ impl RustAnalyzerProject {
     }
 
     /// Write rust-project.json to disk
-    pub fn write_to_disk(&self) -> Result<(), std::io::Error> {
-        std::fs::write(
-            "./rust-project.json",
-            serde_json::to_vec(&self).expect("Failed to serialize to JSON"),
-        )?;
-        Ok(())
+    pub fn write_to_disk(&self) -> Result<(), Error> {
+        let path = "./rust-project.json";
+        let json = serde_json::to_vec(&self).map_err(|e| Error::io("write", path, e.into()))?;
+        std::fs::write(path, json).map_err(|e| Error::io("write", path, e))
     }
 
-    /// If path contains .rs extension, add a crate to `rust-project.json`
-    fn path_to_json(&mut self, path: PathBuf) -> Result<(), Box<dyn Error>> {
-        if let Some(ext) = path.extension() {
-            if ext == "rs" {
-                self.crates.push(Crate {
-                    root_module: path.display().to_string(),
-                    edition: "2021".to_string(),
-                    deps: Vec::new(),
-                    // This allows rust_analyzer to work inside #[test] blocks
-                    cfg: vec!["test".to_string()],
-                })
+    /// Add a crate to `rust-project.json` for the exercise, built the way
+    /// its runner builds it. Exercises rust-analyzer can't read are skipped.
+    fn exercise_to_json(&mut self, exercise: &Exercise) {
+        let Some(config) = exercise
+            .runner()
+            .ok()
+            .and_then(|runner| runner.lsp_config(exercise))
+        else {
+            return;
+        };
+        self.crates.push(Crate {
+            display_name: exercise.name.clone(),
+            root_module: exercise.path.display().to_string(),
+            edition: exercise.edition.as_str().to_string(),
+            deps: Vec::new(),
+            cfg: config.cfg,
+            env: config.env,
+            build: config.build_file.map(|build_file| Build {
+                label: exercise.name.clone(),
+                build_file: build_file.display().to_string(),
+                target_kind: "bin".to_string(),
+            }),
+        });
+    }
+
+    /// Create a `crate` in rust-project.json for every exercise, which
+    /// allows rust-analyzer to treat it like a normal binary. Other files,
+    /// like build scripts, are left out.
+    pub fn exercises_to_json(&mut self, exercises: &[Exercise]) {
+        for exercise in exercises {
+            if exercise.path.extension().is_some_and(|ext| ext == "rs") {
+                self.exercise_to_json(exercise);
             }
         }
+    }
 
+    /// Use `rustc` to determine the default toolchain
+    pub fn get_sysroot_src(&mut self) -> Result<(), Error> {
+        let path = sysroot_src()?;
+        self.sysroot_src = path.to_string_lossy().to_string();
         Ok(())
     }
+}
 
-    /// Parse the exercises folder for .rs files, any matches will create
-    /// a new `crate` in rust-project.json which allows rust-analyzer to
-    /// treat it like a normal binary
-    pub fn exercises_to_json(&mut self) -> Result<(), Box<dyn Error>> {
-        for path in glob("./exercises/**/*")? {
-            self.path_to_json(path?)?;
-        }
-        Ok(())
+/// The sources of the standard library, from RUST_SRC_PATH or the toolchain
+/// `rustc` belongs to
+pub fn sysroot_src() -> Result<PathBuf, Error> {
+    // check if RUST_SRC_PATH is set
+    if let Ok(path) = env::var("RUST_SRC_PATH") {
+        return Ok(PathBuf::from(path));
     }
 
-    /// Use `rustc` to determine the default toolchain
-    pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
-        // check if RUST_SRC_PATH is set
-        if let Ok(path) = env::var("RUST_SRC_PATH") {
-            self.sysroot_src = path;
-            return Ok(());
-        }
+    let toolchain = Command::new("rustc")
+        .arg("--print")
+        .arg("sysroot")
+        .output()
+        .map_err(|source| Error::Spawn {
+            program: "rustc".to_string(),
+            source,
+        })?
+        .stdout;
 
-        let toolchain = Command::new("rustc")
-            .arg("--print")
-            .arg("sysroot")
-            .output()?
-            .stdout;
+    let toolchain = String::from_utf8_lossy(&toolchain);
+    let mut whitespace_iter = toolchain.split_whitespace();
 
-        let toolchain = String::from_utf8_lossy(&toolchain);
-        let mut whitespace_iter = toolchain.split_whitespace();
+    let toolchain = whitespace_iter.next().unwrap_or(&toolchain);
 
-        let toolchain = whitespace_iter.next().unwrap_or(&toolchain);
+    Ok(Path::new(toolchain)
+        .join("lib")
+        .join("rustlib")
+        .join("src")
+        .join("rust")
+        .join("library"))
+}
 
-        println!("Determined toolchain: {}\n", &toolchain);
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        self.sysroot_src = (std::path::Path::new(&*toolchain)
-            .join("lib")
-            .join("rustlib")
-            .join("src")
-            .join("rust")
-            .join("library")
-            .to_string_lossy())
-        .to_string();
-        Ok(())
+    #[test]
+    fn test_scan_build_script() {
+        let source = r#"
+fn main() {
+    let your_command = format!("rustc-env=TEST_FOO={}", timestamp);
+    println!("cargo:{}", your_command);
+    println!("cargo:rustc-env=GREETING=hello");
+    let your_command = "rustc-cfg=feature=\"pass\"";
+    println!("cargo:{}", your_command);
+}
+"#;
+        let output = scan_build_script(source);
+        assert_eq!(
+            output.env,
+            BTreeMap::from([
+                ("GREETING".to_string(), "hello".to_string()),
+                ("TEST_FOO".to_string(), String::new()),
+            ])
+        );
+        assert_eq!(output.cfg, ["feature=\"pass\""]);
     }
 }
@@ -0,0 +1,128 @@
+use crate::evaluate::{evaluate, Outcome};
+use crate::exercise::Exercise;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+
+// How an exercise fared when the whole course was checked
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    Passed,
+    // It works, but still has its `I AM NOT DONE` marker
+    NotDone,
+    CompileError,
+    TestFailure,
+}
+
+fn check(exercise: &Exercise) -> Verdict {
+    match evaluate(exercise) {
+        Outcome::Success(_) if exercise.looks_done() => Verdict::Passed,
+        Outcome::Success(_) => Verdict::NotDone,
+        Outcome::CompileError(_) => Verdict::CompileError,
+        Outcome::RunError(_) => Verdict::TestFailure,
+    }
+}
+
+// Check every exercise, with up to `jobs` of them at a time, and call
+// `checked` with every verdict as soon as it's in. Only exercises whose
+// runner is `parallel_safe` are checked side by side, the others one after
+// another.
+pub fn check_all(
+    exercises: &[Exercise],
+    jobs: usize,
+    checked: impl Fn(&Exercise, Verdict) + Sync,
+) -> Vec<Verdict> {
+    let verdicts = Mutex::new(vec![Verdict::NotDone; exercises.len()]);
+    let record = |i: usize, verdict: Verdict| {
+        checked(&exercises[i], verdict);
+        verdicts.lock().unwrap_or_else(PoisonError::into_inner)[i] = verdict;
+    };
+    let (parallel, sequential): (Vec<usize>, Vec<usize>) = (0..exercises.len()).partition(|&i| {
+        exercises[i]
+            .runner()
+            .is_ok_and(|runner| runner.parallel_safe())
+    });
+
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| {
+                while let Some(&i) = parallel.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    record(i, check(&exercises[i]));
+                }
+            });
+        }
+    });
+    for i in sequential {
+        record(i, check(&exercises[i]));
+    }
+    verdicts
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Default)]
+pub struct Counts {
+    pub passed: usize,
+    pub failed: usize,
+    pub not_done: usize,
+}
+
+impl Counts {
+    pub fn add(&mut self, verdict: Verdict) {
+        match verdict {
+            Verdict::Passed => self.passed += 1,
+            Verdict::NotDone => self.not_done += 1,
+            Verdict::CompileError | Verdict::TestFailure => self.failed += 1,
+        }
+    }
+}
+
+// The passed, failed and not done exercises of every track, in course order
+pub fn summary<'a>(exercises: &'a [Exercise], verdicts: &[Verdict]) -> Vec<(&'a str, Counts)> {
+    let mut order = Vec::new();
+    let mut counts: BTreeMap<&str, Counts> = BTreeMap::new();
+    for (exercise, &verdict) in exercises.iter().zip(verdicts) {
+        let track = exercise.track().unwrap_or("-");
+        if !counts.contains_key(track) {
+            order.push(track);
+        }
+        counts.entry(track).or_default().add(verdict);
+    }
+    order
+        .into_iter()
+        .map(|track| (track, counts.remove(track).unwrap_or_default()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn exercise(path: &str) -> Exercise {
+        Exercise {
+            name: path.to_string(),
+            path: PathBuf::from(path),
+            mode: "compile".to_string(),
+            ..Exercise::default()
+        }
+    }
+
+    #[test]
+    fn test_summary_per_track() {
+        let exercises = [
+            exercise("exercises/intro/intro1.rs"),
+            exercise("exercises/intro/intro2.rs"),
+            exercise("exercises/variables/variables1.rs"),
+        ];
+        let verdicts = [Verdict::Passed, Verdict::NotDone, Verdict::CompileError];
+        let rows = summary(&exercises, &verdicts);
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|(track, c)| (*track, c.passed, c.failed, c.not_done))
+            .collect();
+        assert_eq!(rows, [("intro", 1, 0, 1), ("variables", 0, 1, 0)]);
+    }
+}
@@ -1,40 +1,37 @@
-use std::process::Command;
-
-use crate::exercise::{Exercise, Mode};
 use crate::verify::test;
 use indicatif::ProgressBar;
+use rustlings::error::Error;
+use rustlings::exercise::{Exercise, RunInput};
+use rustlings::runner::Check;
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
-        Mode::BuildScript => test(exercise, verbose)?,
-    }
-    Ok(())
+pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), Error> {
+    run_with(exercise, &RunInput::default(), verbose)
 }
 
-// Resets the exercise by stashing the changes.
-pub fn reset(exercise: &Exercise) -> Result<(), ()> {
-    let command = Command::new("git")
-        .args(["stash", "--"])
-        .arg(&exercise.path)
-        .spawn();
-
-    match command {
-        Ok(_) => Ok(()),
-        Err(_) => Err(()),
+// Like `run`, passing the arguments and input on to the binary. Test
+// exercises take libtest filters and flags, and show which tests ran.
+pub fn run_with(exercise: &Exercise, input: &RunInput, verbose: bool) -> Result<(), Error> {
+    let runner = exercise.runner().inspect_err(|error| warn!("{}", error))?;
+    match runner.check() {
+        Check::Test if input.stdin.is_some() => {
+            let message = format!("{exercise} is run as tests, which don't read stdin");
+            warn!("{}", message);
+            return Err(Error::Input(message));
+        }
+        Check::Test => test(exercise, input, verbose || !input.args.is_empty())?,
+        Check::Run | Check::CompileOnly => compile_and_run(exercise, input)?,
     }
+    Ok(())
 }
 
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
 // This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
+fn compile_and_run(exercise: &Exercise, input: &RunInput) -> Result<(), Error> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Compiling {exercise}..."));
     progress_bar.enable_steady_tick(100);
@@ -42,19 +39,24 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
     let compilation_result = exercise.compile();
     let compilation = match compilation_result {
         Ok(compilation) => compilation,
-        Err(output) => {
+        Err(Error::Compile(output)) => {
             progress_bar.finish_and_clear();
             warn!(
                 "Compilation of {} failed!, Compiler error message:\n",
                 exercise
             );
             println!("{}", output.stderr);
-            return Err(());
+            return Err(Error::Compile(output));
+        }
+        Err(error) => {
+            progress_bar.finish_and_clear();
+            warn!("{}", error);
+            return Err(error);
         }
     };
 
     progress_bar.set_message(format!("Running {exercise}..."));
-    let result = compilation.run();
+    let result = compilation.run_with(input);
     progress_bar.finish_and_clear();
 
     match result {
@@ -63,12 +65,16 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
             success!("Successfully ran {}", exercise);
             Ok(())
         }
-        Err(output) => {
+        Err(Error::Run(output)) => {
             println!("{}", output.stdout);
             println!("{}", output.stderr);
 
             warn!("Ran {} with errors", exercise);
-            Err(())
+            Err(Error::Run(output))
+        }
+        Err(error) => {
+            warn!("{}", error);
+            Err(error)
         }
     }
 }
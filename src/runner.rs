@@ -0,0 +1,655 @@
+use crate::doctest::Doctest;
+use crate::error::{output_of, Error};
+use crate::exercise::{rustc_color_args, Exercise, ExerciseOutput, RunInput};
+use crate::project::scan_build_script;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+// How verifying an exercise checks it once it compiled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check {
+    // Compiling is all there is to it, like for clippy exercises
+    CompileOnly,
+    // The program is run and its output is shown to the learner
+    Run,
+    // The program is a test harness, whose report is only shown when asked for
+    Test,
+}
+
+// How rust-analyzer should see an exercise
+#[derive(Default, Debug, PartialEq)]
+pub struct LspConfig {
+    // The `--cfg` flags the exercise is built with
+    pub cfg: Vec<String>,
+    // The environment variables set while compiling the exercise
+    pub env: BTreeMap<String, String>,
+    // The Cargo manifest of exercises built with cargo
+    pub build_file: Option<PathBuf>,
+}
+
+// A setting of an exercise in info.toml that its mode can't work with
+#[derive(Debug, PartialEq)]
+pub struct InvalidSetting {
+    // The key the problem is reported at, like `command`
+    pub field: &'static str,
+    pub message: String,
+}
+
+// The settings among `fields` that the exercise sets although only another
+// built-in mode uses them
+pub fn stray_settings(exercise: &Exercise, fields: &[&'static str]) -> Vec<InvalidSetting> {
+    let settings = [
+        ("command", exercise.command.is_some(), "custom"),
+        ("build", exercise.build.is_some(), "custom"),
+        (
+            "min_documented",
+            exercise.min_documented.is_some(),
+            "doctest",
+        ),
+    ];
+    settings
+        .into_iter()
+        .filter(|(field, set, _)| *set && fields.contains(field))
+        .map(|(field, _, mode)| InvalidSetting {
+            field,
+            message: format!("`{field}` only applies to mode `{mode}`"),
+        })
+        .collect()
+}
+
+// Everything rustlings needs to know about one `mode` of exercises. The
+// built-in modes are runners like any other, and new ones are added with
+// `register`.
+pub trait ExerciseRunner: Send + Sync {
+    // Build the exercise, putting the program to run at `binary` if there is one
+    fn compile(&self, exercise: &Exercise, binary: &Path) -> Result<(), Error>;
+
+    // Run what `compile` built, with the arguments and input of the learner
+    fn run(
+        &self,
+        exercise: &Exercise,
+        binary: &Path,
+        input: &RunInput,
+    ) -> Result<ExerciseOutput, Error>;
+
+    // The message celebrating that the exercise works
+    fn success_message(&self) -> String;
+
+    fn check(&self) -> Check {
+        Check::Run
+    }
+
+    // How rust-analyzer should see the exercise, None for exercises it
+    // can't make sense of
+    fn lsp_config(&self, _exercise: &Exercise) -> Option<LspConfig> {
+        Some(LspConfig::default())
+    }
+
+    // Build the exercise with debug info to `target` for `rustlings debug`.
+    // With `Check::Test`, the program is expected to be a libtest harness.
+    fn compile_debug(&self, exercise: &Exercise, _target: &Path) -> Result<(), Error> {
+        Err(Error::Input(format!(
+            "{exercise} isn't built into a program, so it can't be debugged"
+        )))
+    }
+
+    // Whether exercises of this mode can be checked side by side with others
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
+    // The files besides the exercise itself that it's built from, like the
+    // build script of a crate. Watch mode keeps an eye on them, and grading
+    // a submission copies them along with the exercise.
+    fn extra_files(&self, _exercise: &Exercise) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    // The settings of the exercise this mode can't work with, which
+    // check-config reports. By default, those only other modes use.
+    fn validate(&self, exercise: &Exercise) -> Vec<InvalidSetting> {
+        stray_settings(exercise, &["command", "build", "min_documented"])
+    }
+}
+
+type Registry = HashMap<String, Arc<dyn ExerciseRunner>>;
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut runners: Registry = HashMap::new();
+        runners.insert("compile".to_string(), Arc::new(Rustc { test: false }));
+        runners.insert("test".to_string(), Arc::new(Rustc { test: true }));
+        runners.insert("clippy".to_string(), Arc::new(Clippy));
+        runners.insert("buildscript".to_string(), Arc::new(BuildScript));
+        runners.insert("doctest".to_string(), Arc::new(Doctest));
+        runners.insert("custom".to_string(), Arc::new(Custom));
+        RwLock::new(runners)
+    })
+}
+
+// Run the exercises whose `mode` is `mode` with `runner`, replacing the
+// runner registered for it before
+pub fn register(mode: &str, runner: impl ExerciseRunner + 'static) {
+    // Inserting can't leave the registry half-changed, so it stays usable
+    // even if a thread panicked while holding the lock
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(mode.to_string(), Arc::new(runner));
+}
+
+// The runner for the exercises of `mode`
+pub fn get(mode: &str) -> Result<Arc<dyn ExerciseRunner>, Error> {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(mode)
+        .cloned()
+        .ok_or_else(|| {
+            Error::Config(format!(
+                "unknown mode '{mode}', expected one of: {}",
+                modes().join(", ")
+            ))
+        })
+}
+
+// The modes there is a runner for, in alphabetical order
+pub fn modes() -> Vec<String> {
+    let registry = registry().read().unwrap_or_else(PoisonError::into_inner);
+    let mut modes: Vec<String> = registry.keys().cloned().collect();
+    modes.sort();
+    modes
+}
+
+// Run the command with the arguments and input of the learner, failing
+// with its output if it doesn't succeed
+pub fn run_binary(mut cmd: Command, input: &RunInput) -> Result<ExerciseOutput, Error> {
+    cmd.args(&input.args);
+    if let Some(path) = &input.stdin {
+        let file = File::open(path).map_err(|e| Error::io("read", path, e))?;
+        cmd.stdin(file);
+    }
+    let cmd = output_of(&mut cmd)?;
+    let output = ExerciseOutput::from(&cmd);
+    if cmd.status.success() {
+        Ok(output)
+    } else {
+        Err(Error::Run(output))
+    }
+}
+
+// Fail with the compiler output unless the command succeeded
+fn compiled(cmd: &mut Command) -> Result<(), Error> {
+    let output = output_of(cmd)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::Compile(ExerciseOutput::from(&output)))
+    }
+}
+
+fn rustc(exercise: &Exercise, binary: &Path) -> Command {
+    let mut cmd = Command::new("rustc");
+    cmd.arg(&exercise.path)
+        .arg("-o")
+        .arg(binary)
+        .args(rustc_color_args())
+        .args(["--edition", exercise.edition.as_str()]);
+    if exercise.denies_warnings() {
+        cmd.args(["-D", "warnings"]);
+    }
+    cmd
+}
+
+// `cargo test`, which has no flag for denying warnings so they're denied
+// through the environment
+fn cargo_test(exercise: &Exercise) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("test");
+    if exercise.denies_warnings() {
+        cmd.env("RUSTFLAGS", "-D warnings");
+    }
+    cmd
+}
+
+// Build the exercise with rustc and debug info, as a test harness if `test`
+fn rustc_debug(exercise: &Exercise, target: &Path, test: bool) -> Result<(), Error> {
+    let mut cmd = Command::new("rustc");
+    if test {
+        cmd.arg("--test");
+    }
+    cmd.arg(&exercise.path)
+        .args(["-g", "-C", "opt-level=0", "-o"])
+        .arg(target)
+        .args(rustc_color_args())
+        .args(["--edition", exercise.edition.as_str()]);
+    compiled(&mut cmd)
+}
+
+// The files of the crate the exercise is built in with cargo
+fn crate_files(exercise: &Exercise) -> Vec<PathBuf> {
+    ["build.rs", "Cargo.toml"]
+        .into_iter()
+        .map(|name| exercise.path.with_file_name(name))
+        .collect()
+}
+
+// Exercises compiled with rustc, as a binary or as a test harness
+struct Rustc {
+    test: bool,
+}
+
+impl ExerciseRunner for Rustc {
+    fn compile(&self, exercise: &Exercise, binary: &Path) -> Result<(), Error> {
+        let mut cmd = rustc(exercise, binary);
+        if self.test {
+            cmd.arg("--test");
+        }
+        compiled(&mut cmd)
+    }
+
+    fn run(
+        &self,
+        _exercise: &Exercise,
+        binary: &Path,
+        input: &RunInput,
+    ) -> Result<ExerciseOutput, Error> {
+        let mut cmd = Command::new(binary);
+        if self.test {
+            cmd.arg("--show-output");
+        }
+        run_binary(cmd, input)
+    }
+
+    fn success_message(&self) -> String {
+        if self.test {
+            "The code is compiling, and the tests pass!".to_string()
+        } else {
+            "The code is compiling!".to_string()
+        }
+    }
+
+    fn check(&self) -> Check {
+        if self.test {
+            Check::Test
+        } else {
+            Check::Run
+        }
+    }
+
+    fn lsp_config(&self, _exercise: &Exercise) -> Option<LspConfig> {
+        // This allows rust_analyzer to work inside #[test] blocks
+        let cfg = if self.test {
+            vec!["test".to_string()]
+        } else {
+            Vec::new()
+        };
+        Some(LspConfig {
+            cfg,
+            ..LspConfig::default()
+        })
+    }
+
+    fn compile_debug(&self, exercise: &Exercise, target: &Path) -> Result<(), Error> {
+        rustc_debug(exercise, target, self.test)
+    }
+
+    // Every exercise gets its own binary
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+}
+
+// Exercises linted with clippy, through a Cargo manifest next to them
+struct Clippy;
+
+impl ExerciseRunner for Clippy {
+    fn compile(&self, exercise: &Exercise, binary: &Path) -> Result<(), Error> {
+        exercise.write_cargo_toml()?;
+        let manifest = exercise.cargo_toml_path();
+        // To support the ability to run the clippy exercises, build
+        // an executable, in addition to running clippy. With a
+        // compilation failure, this would silently fail. But we expect
+        // clippy to reflect the same failure while compiling later.
+        output_of(&mut rustc(exercise, binary))?;
+        // Due to an issue with Clippy, a cargo clean is required to catch all lints.
+        // See https://github.com/rust-lang/rust-clippy/issues/2604
+        // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
+        // https://github.com/rust-lang/rust-clippy/issues/3837
+        output_of(
+            Command::new("cargo")
+                .args(["clean", "--manifest-path"])
+                .arg(&manifest)
+                .args(rustc_color_args()),
+        )?;
+        compiled(
+            Command::new("cargo")
+                .args(["clippy", "--manifest-path"])
+                .arg(&manifest)
+                .args(rustc_color_args())
+                .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"]),
+        )
+    }
+
+    fn run(
+        &self,
+        _exercise: &Exercise,
+        binary: &Path,
+        input: &RunInput,
+    ) -> Result<ExerciseOutput, Error> {
+        run_binary(Command::new(binary), input)
+    }
+
+    fn success_message(&self) -> String {
+        if env::var("NO_EMOJI").is_ok() {
+            "The code is compiling, and Clippy is happy!".to_string()
+        } else {
+            "The code is compiling, and 📎 Clippy 📎 is happy!".to_string()
+        }
+    }
+
+    fn check(&self) -> Check {
+        Check::CompileOnly
+    }
+
+    fn lsp_config(&self, exercise: &Exercise) -> Option<LspConfig> {
+        Some(LspConfig {
+            build_file: Some(exercise.cargo_toml_path()),
+            ..LspConfig::default()
+        })
+    }
+
+    fn compile_debug(&self, exercise: &Exercise, target: &Path) -> Result<(), Error> {
+        rustc_debug(exercise, target, false)
+    }
+
+    fn extra_files(&self, exercise: &Exercise) -> Vec<PathBuf> {
+        crate_files(exercise)
+    }
+}
+
+// Exercises with a build script, tested by cargo
+struct BuildScript;
+
+impl ExerciseRunner for BuildScript {
+    fn compile(&self, exercise: &Exercise, _binary: &Path) -> Result<(), Error> {
+        exercise.write_cargo_toml()?;
+        compiled(
+            cargo_test(exercise)
+                .arg("--manifest-path")
+                .arg(exercise.cargo_toml_path()),
+        )
+    }
+
+    // `compile` already ran the tests
+    fn run(
+        &self,
+        exercise: &Exercise,
+        _binary: &Path,
+        input: &RunInput,
+    ) -> Result<ExerciseOutput, Error> {
+        if !input.args.is_empty() || input.stdin.is_some() {
+            return Err(Error::Input(format!(
+                "{exercise} is tested by cargo, which takes no arguments here"
+            )));
+        }
+        Ok(ExerciseOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+        })
+    }
+
+    fn success_message(&self) -> String {
+        "Build script works!".to_string()
+    }
+
+    fn check(&self) -> Check {
+        Check::Test
+    }
+
+    fn lsp_config(&self, exercise: &Exercise) -> Option<LspConfig> {
+        let mut config = LspConfig {
+            cfg: vec!["test".to_string()],
+            build_file: Some(exercise.cargo_toml_path()),
+            ..LspConfig::default()
+        };
+        if let Ok(source) = fs::read_to_string(exercise.path.with_file_name("build.rs")) {
+            let output = scan_build_script(&source);
+            config.cfg.extend(output.cfg);
+            config.env = output.env;
+        }
+        Some(config)
+    }
+
+    // Cargo keeps the test harness in its own target directory, it's copied
+    // to `target` from there
+    fn compile_debug(&self, exercise: &Exercise, target: &Path) -> Result<(), Error> {
+        exercise.write_cargo_toml()?;
+        let cmd = output_of(
+            cargo_test(exercise)
+                .args(["--no-run", "--message-format=json", "--manifest-path"])
+                .arg(exercise.cargo_toml_path()),
+        )?;
+        let mut output = ExerciseOutput::from(&cmd);
+        if !cmd.status.success() {
+            return Err(Error::Compile(output));
+        }
+        let executable = output
+            .stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .find_map(|message| message["executable"].as_str().map(PathBuf::from));
+        let copied = match executable {
+            Some(executable) => fs::copy(executable, target).map_err(|e| e.to_string()),
+            None => Err("cargo didn't build a test harness".to_string()),
+        };
+        if let Err(e) = copied {
+            output.stderr = format!("Could not put the test harness in place: {e}");
+            return Err(Error::Compile(output));
+        }
+        Ok(())
+    }
+
+    fn extra_files(&self, exercise: &Exercise) -> Vec<PathBuf> {
+        crate_files(exercise)
+    }
+}
+
+// Exercises run by the commands given in info.toml, like
+// `command = "python3 {path}"`, with an optional `build` command run first.
+// `{path}`, `{name}` and `{binary}` are replaced by the path of the
+// exercise, its name and a scratch file the build can write a program to.
+struct Custom;
+
+// Split the template into a program and its arguments, and fill in the
+// placeholders of every part
+fn command_from(template: &str, exercise: &Exercise, binary: &Path) -> Result<Command, Error> {
+    let mut parts = template.split_whitespace().map(|part| {
+        part.replace("{path}", &exercise.path.display().to_string())
+            .replace("{name}", &exercise.name)
+            .replace("{binary}", &binary.display().to_string())
+    });
+    let program = parts
+        .next()
+        .ok_or_else(|| Error::Config(format!("{exercise} has an empty command")))?;
+    let mut cmd = Command::new(program);
+    cmd.args(parts);
+    Ok(cmd)
+}
+
+impl ExerciseRunner for Custom {
+    fn compile(&self, exercise: &Exercise, binary: &Path) -> Result<(), Error> {
+        match &exercise.build {
+            Some(build) => compiled(&mut command_from(build, exercise, binary)?),
+            None => Ok(()),
+        }
+    }
+
+    fn run(
+        &self,
+        exercise: &Exercise,
+        binary: &Path,
+        input: &RunInput,
+    ) -> Result<ExerciseOutput, Error> {
+        let template = exercise.command.as_deref().ok_or_else(|| {
+            Error::Config(format!("{exercise} has mode `custom` but no `command`"))
+        })?;
+        run_binary(command_from(template, exercise, binary)?, input)
+    }
+
+    fn success_message(&self) -> String {
+        "The exercise works!".to_string()
+    }
+
+    // The commands may run anything, which rust-analyzer can't make sense of
+    fn lsp_config(&self, _exercise: &Exercise) -> Option<LspConfig> {
+        None
+    }
+
+    fn validate(&self, exercise: &Exercise) -> Vec<InvalidSetting> {
+        let mut invalid = stray_settings(exercise, &["min_documented"]);
+        if exercise.command.is_none() {
+            invalid.push(InvalidSetting {
+                field: "mode",
+                message: "exercise has mode `custom` but no `command`".to_string(),
+            });
+        }
+        // Only the commands know how to build the exercise, so rustlings
+        // can't turn its warnings into errors
+        if exercise.deny_warnings == Some(true) {
+            invalid.push(InvalidSetting {
+                field: "deny_warnings",
+                message: "`deny_warnings` doesn't apply to mode `custom`, \
+                          pass `-D warnings` in its `build` command instead"
+                    .to_string(),
+            });
+        }
+        invalid
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Echo;
+
+    impl ExerciseRunner for Echo {
+        fn compile(&self, _exercise: &Exercise, _binary: &Path) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn run(
+            &self,
+            exercise: &Exercise,
+            _binary: &Path,
+            input: &RunInput,
+        ) -> Result<ExerciseOutput, Error> {
+            Ok(ExerciseOutput {
+                stdout: format!("{} {}", exercise.name, input.args.join(" ")),
+                stderr: String::new(),
+            })
+        }
+
+        fn success_message(&self) -> String {
+            "Echoed!".to_string()
+        }
+    }
+
+    #[test]
+    fn test_registered_runner() {
+        let toml = r#"
+            [[exercises]]
+            name = "echo1"
+            path = "exercises/echo/echo1.txt"
+            mode = "echo"
+            hint = ""
+        "#;
+        let list: crate::ExerciseList = toml::from_str(toml).unwrap();
+        let exercise = &list.exercises[0];
+        assert!(matches!(exercise.compile(), Err(Error::Config(_))));
+
+        register("echo", Echo);
+        assert!(modes().contains(&"echo".to_string()));
+        let input = RunInput {
+            args: vec!["hi".to_string()],
+            stdin: None,
+        };
+        let output = exercise.compile().unwrap().run_with(&input).unwrap();
+        assert_eq!(output.stdout, "echo1 hi");
+        assert_eq!(exercise.runner().unwrap().success_message(), "Echoed!");
+    }
+
+    #[test]
+    fn test_validate_reports_settings_of_other_modes() {
+        let exercise = Exercise {
+            mode: "compile".to_string(),
+            command: Some("python3 {path}".to_string()),
+            ..Exercise::default()
+        };
+        let invalid = get("compile").unwrap().validate(&exercise);
+        assert_eq!(
+            invalid,
+            vec![InvalidSetting {
+                field: "command",
+                message: "`command` only applies to mode `custom`".to_string(),
+            }]
+        );
+        assert!(get("custom").unwrap().validate(&exercise).is_empty());
+        let denying = Exercise {
+            deny_warnings: Some(true),
+            ..exercise.clone()
+        };
+        let fields: Vec<_> = get("custom")
+            .unwrap()
+            .validate(&denying)
+            .into_iter()
+            .map(|invalid| invalid.field)
+            .collect();
+        assert_eq!(fields, ["deny_warnings"]);
+
+        let exercise = Exercise {
+            min_documented: Some(1),
+            ..exercise
+        };
+        let fields: Vec<_> = get("doctest")
+            .unwrap()
+            .validate(&exercise)
+            .into_iter()
+            .map(|invalid| invalid.field)
+            .collect();
+        assert_eq!(fields, ["command"]);
+    }
+
+    #[test]
+    fn test_custom_command_template() {
+        let toml = r#"
+            [[exercises]]
+            name = "shell1"
+            path = "exercises/shell/shell1.sh"
+            mode = "custom"
+            command = "sh {path} --name={name}"
+            hint = ""
+        "#;
+        let list: crate::ExerciseList = toml::from_str(toml).unwrap();
+        let exercise = &list.exercises[0];
+        let cmd = command_from(
+            exercise.command.as_deref().unwrap(),
+            exercise,
+            Path::new("./temp"),
+        )
+        .unwrap();
+        assert_eq!(cmd.get_program(), "sh");
+        assert_eq!(
+            cmd.get_args().collect::<Vec<_>>(),
+            ["exercises/shell/shell1.sh", "--name=shell1"]
+        );
+        // Scripts aren't crates rust-analyzer could read
+        assert_eq!(Custom.lsp_config(exercise), None);
+    }
+}
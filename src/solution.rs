@@ -0,0 +1,111 @@
+use crate::diff::print_changes_between;
+use rustlings::exercise::Exercise;
+use rustlings::{evaluate, Outcome};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+// The reference solutions, laid out like the exercises
+const SOLUTIONS_DIR: &str = "solutions";
+
+// Whether the course ships reference solutions at all
+pub fn has_solutions() -> bool {
+    Path::new(SOLUTIONS_DIR).is_dir()
+}
+
+// Where the reference solution of an exercise lives: at the same place in
+// `solutions/` as the exercise in `exercises/`
+pub fn solution_path(exercise: &Exercise) -> PathBuf {
+    let mut components = exercise
+        .path
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .peekable();
+    components.next_if(|c| c.as_os_str() == "exercises");
+    Path::new(SOLUTIONS_DIR).join(components.collect::<PathBuf>())
+}
+
+// The exercise with its reference solution in place of the learner's file,
+// if there is a solution for it
+pub fn solved(exercise: &Exercise) -> Option<Exercise> {
+    let path = solution_path(exercise);
+    path.exists().then(|| Exercise {
+        path,
+        ..exercise.clone()
+    })
+}
+
+// Print the reference solution of the exercise, or how it differs from the
+// learner's version with `diff`. Solutions stay locked until the exercise
+// passes.
+pub fn print_solution(exercise: &Exercise, diff: bool) -> Result<(), String> {
+    let path = solution_path(exercise);
+    let solution = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "There is no solution for {exercise} at {}: {e}",
+            path.display()
+        )
+    })?;
+    let passes = exercise.looks_done() && matches!(evaluate(exercise), Outcome::Success(_));
+    if !passes {
+        return Err(format!(
+            "The solution of {exercise} unlocks once the exercise passes, keep going!"
+        ));
+    }
+
+    if diff {
+        let yours = fs::read_to_string(&exercise.path).map_err(|e| e.to_string())?;
+        let labels = (exercise.path.display(), path.display());
+        if !print_changes_between(&yours, &solution, labels) {
+            println!("Your version is the same as the solution.");
+        }
+    } else {
+        print!("{solution}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rustlings::ExerciseList;
+
+    #[test]
+    fn test_solution_path() {
+        let list = ExerciseList::load(Path::new("info.toml")).unwrap();
+        let mut exercise = list.exercises[0].clone();
+        assert_eq!(
+            solution_path(&exercise),
+            Path::new("solutions/intro/intro2.rs")
+        );
+        // Exercises outside of `exercises/` keep their whole path
+        exercise.path = PathBuf::from("tests/fixture/config/good_exercise.rs");
+        assert_eq!(
+            solution_path(&exercise),
+            Path::new("solutions/tests/fixture/config/good_exercise.rs")
+        );
+        exercise.path = PathBuf::from("./exercises/tests/tests7.rs");
+        assert_eq!(
+            solution_path(&exercise),
+            Path::new("solutions/tests/tests7.rs")
+        );
+    }
+
+    // If the course ships reference solutions, every exercise has one, and
+    // the solution passes the way the exercise has to
+    #[test]
+    fn test_reference_solutions_pass() {
+        if !Path::new("solutions").is_dir() {
+            return;
+        }
+        let list = ExerciseList::load(Path::new("info.toml")).unwrap();
+        for exercise in &list.exercises {
+            let solved = solved(exercise)
+                .unwrap_or_else(|| panic!("{} has no reference solution", exercise.name));
+            assert!(
+                matches!(evaluate(&solved), Outcome::Success(_)),
+                "the reference solution of {} fails",
+                exercise.name
+            );
+        }
+    }
+}
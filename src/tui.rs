@@ -0,0 +1,361 @@
+use crate::editor::{failure_location, open_editor, Location};
+use crate::verify::completion_text;
+use crate::watch::{Change, FileWatcher, WatchConfig, WatchStatus, WatchedFiles};
+use console::strip_ansi_codes;
+use notify::DebouncedEvent;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use rustlings::exercise::{
+    estimated_minutes_remaining, format_minutes, Exercise, ExerciseList, ExerciseOutput, State,
+};
+use rustlings::hints::RevealedHints;
+use rustlings::runner::Check;
+use rustlings::{evaluate, Outcome};
+use std::mem;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+// The state of an exercise as shown in the sidebar
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    // Not verified yet in this session
+    Unchecked,
+    Done,
+    // Works, but still has its `I AM NOT DONE` marker
+    Pending,
+    Failed,
+}
+
+struct App {
+    exercises: Vec<Exercise>,
+    statuses: Vec<Status>,
+    // The index of the exercise verification stopped at
+    current: Option<usize>,
+    // The compiler or test output of the current exercise, without colors
+    output: String,
+    scroll: u16,
+    show_hint: bool,
+    hints: RevealedHints,
+    // What's going on right now, shown in the status bar
+    message: String,
+    // Show the output of passing tests, like `--nocapture` does for the shell
+    verbose: bool,
+    // Show the hints of exercises that work but aren't marked as done
+    success_hints: bool,
+}
+
+impl App {
+    fn new(exercises: Vec<Exercise>, verbose: bool, success_hints: bool) -> Self {
+        let statuses = exercises
+            .iter()
+            .map(|e| {
+                if e.looks_done() {
+                    Status::Done
+                } else {
+                    Status::Unchecked
+                }
+            })
+            .collect();
+        App {
+            exercises,
+            statuses,
+            current: None,
+            output: String::new(),
+            scroll: 0,
+            show_hint: false,
+            hints: RevealedHints::load(),
+            message: String::new(),
+            verbose,
+            success_hints,
+        }
+    }
+
+    // Verify the pending exercises starting with `first`, the same way
+    // `verify` does. Returns true once every exercise is done.
+    fn verify_from(&mut self, first: Option<usize>, terminal: &mut DefaultTerminal) -> bool {
+        let order: Vec<usize> = first
+            .into_iter()
+            .chain(
+                (0..self.exercises.len())
+                    .filter(|&i| Some(i) != first && !self.exercises[i].looks_done()),
+            )
+            .collect();
+        for i in order {
+            let exercise = &self.exercises[i];
+            if self.current != Some(i) {
+                self.show_hint = false;
+            }
+            self.current = Some(i);
+            self.message = format!("Checking {exercise}...");
+            let _ = terminal.draw(|frame| self.draw(frame));
+
+            self.scroll = 0;
+            self.message.clear();
+            let (status, output) = match evaluate(exercise) {
+                Outcome::Success(output) => match exercise.state() {
+                    Ok(State::Done) => {
+                        self.statuses[i] = Status::Done;
+                        continue;
+                    }
+                    Err(error) => (Status::Failed, error.to_string()),
+                    Ok(State::Pending(context)) => {
+                        // Test reports are only shown when asked for, like `verify` does
+                        let is_test = exercise
+                            .runner()
+                            .is_ok_and(|runner| runner.check() == Check::Test);
+                        let shown = Some(output.stdout.as_str()).filter(|stdout| {
+                            (self.verbose || !is_test) && !stdout.trim().is_empty()
+                        });
+                        let text = completion_text(exercise, shown, self.success_hints, &context);
+                        (Status::Pending, text)
+                    }
+                },
+                Outcome::CompileError(output) => (Status::Failed, output.stderr),
+                Outcome::RunError(output) => (
+                    Status::Failed,
+                    format!("{}\n{}", output.stdout, output.stderr),
+                ),
+            };
+            self.statuses[i] = status;
+            self.output = strip_ansi_codes(&output).into_owned();
+            return false;
+        }
+        self.current = None;
+        true
+    }
+
+    // Switch to a freshly loaded exercise list, staying on the current
+    // exercise as long as it's still part of the course
+    fn reload(&mut self, exercises: Vec<Exercise>) {
+        let current = self.current.map(|i| self.exercises[i].name.clone());
+        let output = mem::take(&mut self.output);
+        let show_hint = self.show_hint;
+        *self = App::new(exercises, self.verbose, self.success_hints);
+        self.current = current.and_then(|name| self.exercises.iter().position(|e| e.name == name));
+        self.output = output;
+        self.show_hint = show_hint;
+    }
+
+    // Open the hint pane revealing the next hint, or close it
+    fn toggle_hint(&mut self) {
+        if self.show_hint {
+            self.show_hint = false;
+        } else if let Some(i) = self.current {
+            self.hints.reveal_next(&self.exercises[i]);
+            if self.hints.save().is_err() {
+                self.message = "Could not record the revealed hints".to_string();
+            }
+            self.show_hint = true;
+        }
+    }
+
+    // Where the current exercise needs work, if there is one
+    fn location(&self) -> Option<Location> {
+        let exercise = &self.exercises[self.current?];
+        let output = ExerciseOutput {
+            stdout: self.output.clone(),
+            stderr: String::new(),
+        };
+        Some(failure_location(exercise, Some(&output)))
+    }
+
+    fn progress(&self) -> (usize, usize) {
+        let done = self.statuses.iter().filter(|&&s| s == Status::Done).count();
+        (done, self.exercises.len())
+    }
+
+    fn scroll_by(&mut self, lines: i32) {
+        let max = self.output.lines().count().saturating_sub(1) as i32;
+        self.scroll = (self.scroll as i32 + lines).clamp(0, max) as u16;
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status_bar] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, right] =
+            Layout::horizontal([Constraint::Length(26), Constraint::Min(1)]).areas(main);
+        let hint_height = if self.show_hint {
+            Constraint::Percentage(30)
+        } else {
+            Constraint::Length(0)
+        };
+        let [output_area, hint_area] =
+            Layout::vertical([Constraint::Min(1), hint_height]).areas(right);
+
+        let items: Vec<ListItem> = self
+            .exercises
+            .iter()
+            .zip(&self.statuses)
+            .map(|(exercise, status)| {
+                let (symbol, color) = match status {
+                    Status::Unchecked => ("·", Color::Gray),
+                    Status::Done => ("✓", Color::Green),
+                    Status::Pending => ("•", Color::Yellow),
+                    Status::Failed => ("✗", Color::Red),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{symbol} "), Style::default().fg(color)),
+                    Span::raw(exercise.name.as_str()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Exercises "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut list_state = ListState::default().with_selected(self.current);
+        frame.render_stateful_widget(list, sidebar, &mut list_state);
+
+        let title = match self.current {
+            Some(i) => format!(" {} ", self.exercises[i]),
+            None => " Output ".to_string(),
+        };
+        let output = Paragraph::new(self.output.as_str())
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(output, output_area);
+
+        if self.show_hint {
+            let mut hint = String::new();
+            if let Some(exercise) = self.current.map(|i| &self.exercises[i]) {
+                let levels = exercise.hint_levels();
+                for (level, text) in levels.iter().take(self.hints.used(exercise)).enumerate() {
+                    if levels.len() > 1 {
+                        hint += &format!("Hint {}/{}: ", level + 1, levels.len());
+                    }
+                    hint += &format!("{text}\n");
+                }
+            }
+            let hint = Paragraph::new(hint)
+                .block(Block::bordered().title(" Hint "))
+                .wrap(Wrap { trim: false });
+            frame.render_widget(hint, hint_area);
+        }
+
+        let (done, total) = self.progress();
+        let mut status = format!(
+            " Progress: {done}/{total} ({:.1} %)",
+            done as f32 / total as f32 * 100.0
+        );
+        let minutes = estimated_minutes_remaining(&self.exercises);
+        if minutes > 0 {
+            status += &format!(" · about {} left", format_minutes(minutes));
+        }
+        status += " │ h: hint  e: edit  ↑↓/PgUp/PgDn: scroll  r: rerun  q: quit";
+        if !self.message.is_empty() {
+            status += &format!(" │ {}", self.message);
+        }
+        let status =
+            Paragraph::new(status).style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(status, status_bar);
+    }
+}
+
+// Watch mode with a full-screen terminal interface instead of a scrolling shell
+pub fn watch_tui(
+    config: &WatchConfig,
+    list: ExerciseList,
+    verbose: bool,
+    success_hints: bool,
+) -> notify::Result<WatchStatus> {
+    let mut terminal = ratatui::init();
+    let status = run_app(config, list, verbose, success_hints, &mut terminal);
+    ratatui::restore();
+    status
+}
+
+fn run_app(
+    config: &WatchConfig,
+    list: ExerciseList,
+    verbose: bool,
+    success_hints: bool,
+    terminal: &mut DefaultTerminal,
+) -> notify::Result<WatchStatus> {
+    let mut files = WatchedFiles::new(config.info, &list);
+    let mut app = App::new(list.exercises, verbose, success_hints);
+    // Watch before the first verification, so that saves made while it's
+    // compiling aren't missed
+    let (tx, rx) = channel();
+    let mut watcher = FileWatcher::new(tx, config.poll)?;
+    watcher.watch_course(&files)?;
+
+    if app.verify_from(None, terminal) {
+        return Ok(WatchStatus::Finished);
+    }
+    files.snapshot();
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        while let Ok(event) = rx.try_recv() {
+            if let DebouncedEvent::Error(e, _) = &event {
+                app.message = format!("watch error: {e:?}");
+                continue;
+            }
+            let first = match files.classify(&event, &app.exercises) {
+                None => continue,
+                Some(Change::Exercise(i)) => Some(i),
+                Some(Change::Config) => match config.load() {
+                    Ok(list) => {
+                        files = WatchedFiles::new(config.info, &list);
+                        watcher.watch_course(&files)?;
+                        app.reload(list.exercises);
+                        app.current
+                    }
+                    Err(e) => {
+                        let e = e.to_string();
+                        app.message = format!(
+                            "Could not reload the exercises: {}",
+                            e.lines().next().unwrap_or_default()
+                        );
+                        continue;
+                    }
+                },
+            };
+            if app.verify_from(first, terminal) {
+                return Ok(WatchStatus::Finished);
+            }
+            files.snapshot();
+            terminal.draw(|frame| app.draw(frame))?;
+        }
+
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(WatchStatus::Unfinished),
+            KeyCode::Char('h') => app.toggle_hint(),
+            KeyCode::Up | KeyCode::Char('k') => app.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => app.scroll_by(1),
+            KeyCode::PageUp => app.scroll_by(-10),
+            KeyCode::PageDown => app.scroll_by(10),
+            KeyCode::Char('e') => {
+                if let Some(location) = app.location() {
+                    // Terminal editors need the terminal to themselves
+                    ratatui::restore();
+                    let opened = open_editor(&location);
+                    *terminal = ratatui::init();
+                    if let Err(e) = opened {
+                        app.message = format!("Could not open your editor: {e}");
+                    }
+                }
+            }
+            KeyCode::Char('r') => {
+                if app.verify_from(app.current, terminal) {
+                    return Ok(WatchStatus::Finished);
+                }
+                files.snapshot();
+            }
+            _ => {}
+        }
+    }
+}
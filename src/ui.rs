@@ -13,6 +13,9 @@ macro_rules! warn {
             );
         }
     }};
+    ($fmt:literal) => {
+        warn!("{}", format!($fmt))
+    };
 }
 
 macro_rules! success {
@@ -30,4 +33,7 @@ macro_rules! success {
             );
         }
     }};
+    ($fmt:literal) => {
+        success!("{}", format!($fmt))
+    };
 }
@@ -1,19 +1,37 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::diagnostics::{print_changes, Diagnostics};
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
+use rustlings::error::Error;
+use rustlings::exercise::{
+    CompiledExercise, ContextLine, Exercise, ExerciseOutput, RunInput, State,
+};
+use rustlings::report::{check_all, summary, Counts, Verdict};
+use rustlings::runner::Check;
 use std::env;
+use std::fmt::Write;
+
+// The exercise at which verification stopped
+pub struct Failure<'a> {
+    pub exercise: &'a Exercise,
+    // The compiler or test output that was shown for the exercise.
+    // This is None if the exercise works but still has its `I AM NOT DONE` marker.
+    pub output: Option<ExerciseOutput>,
+}
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
 // Any such failures will be reported to the end user.
 // If the Exercise being verified is a test, the verbose boolean
 // determines whether or not the test harness outputs are displayed.
+// The `previous` diagnostics of an exercise are compared with the new ones
+// when it fails again.
 pub fn verify<'a>(
     exercises: impl IntoIterator<Item = &'a Exercise>,
     progress: (usize, usize),
     verbose: bool,
     success_hints: bool,
-) -> Result<(), &'a Exercise> {
+    previous: Option<&Diagnostics>,
+) -> Result<(), Failure<'a>> {
     let (num_done, total) = progress;
     let bar = ProgressBar::new(total as u64);
     let mut percentage = num_done as f32 / total as f32 * 100.0;
@@ -25,15 +43,32 @@ pub fn verify<'a>(
     bar.set_message(format!("({:.1} %)", percentage));
 
     for exercise in exercises {
-        let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-            Mode::Compile => compile_and_run_interactively(exercise, success_hints),
-            Mode::Clippy => compile_only(exercise, success_hints),
-            Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-
-        };
-        if !compile_result.unwrap_or(false) {
-            return Err(exercise);
+        let compile_result = exercise.runner().and_then(|runner| match runner.check() {
+            Check::Test => compile_and_test(
+                exercise,
+                RunMode::Interactive,
+                &RunInput::default(),
+                verbose,
+                success_hints,
+                previous,
+            ),
+            Check::Run => compile_and_run_interactively(exercise, success_hints, previous),
+            Check::CompileOnly => compile_only(exercise, success_hints, previous),
+        });
+        match compile_result {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(Failure {
+                    exercise,
+                    output: None,
+                })
+            }
+            Err(error) => {
+                return Err(Failure {
+                    exercise,
+                    output: Some(error.into_output()),
+                })
+            }
         }
         percentage += 100.0 / total as f32;
         bar.inc(1);
@@ -47,31 +82,47 @@ enum RunMode {
     NonInteractive,
 }
 
-// Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    compile_and_test(exercise, RunMode::NonInteractive, verbose, false)?;
+// Compile and run the resulting test harness of the given Exercise,
+// with the filters and flags in `input`
+pub fn test(exercise: &Exercise, input: &RunInput, verbose: bool) -> Result<(), Error> {
+    compile_and_test(
+        exercise,
+        RunMode::NonInteractive,
+        input,
+        verbose,
+        false,
+        None,
+    )?;
     Ok(())
 }
 
 // Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn compile_only(
+    exercise: &Exercise,
+    success_hints: bool,
+    previous: Option<&Diagnostics>,
+) -> Result<bool, Error> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Compiling {exercise}..."));
     progress_bar.enable_steady_tick(100);
 
-    let _ = compile(exercise, &progress_bar)?;
+    let _ = compile(exercise, &progress_bar, previous)?;
     progress_bar.finish_and_clear();
 
     Ok(prompt_for_completion(exercise, None, success_hints))
 }
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn compile_and_run_interactively(
+    exercise: &Exercise,
+    success_hints: bool,
+    previous: Option<&Diagnostics>,
+) -> Result<bool, Error> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Compiling {exercise}..."));
     progress_bar.enable_steady_tick(100);
 
-    let compilation = compile(exercise, &progress_bar)?;
+    let compilation = compile(exercise, &progress_bar, previous)?;
 
     progress_bar.set_message(format!("Running {exercise}..."));
     let result = compilation.run();
@@ -79,11 +130,16 @@ fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Re
 
     let output = match result {
         Ok(output) => output,
-        Err(output) => {
+        Err(Error::Run(output)) => {
+            print_changes(previous, exercise, &output);
             warn!("Ran {} with errors", exercise);
             println!("{}", output.stdout);
             println!("{}", output.stderr);
-            return Err(());
+            return Err(Error::Run(output));
+        }
+        Err(error) => {
+            warn!("{}", error);
+            return Err(error);
         }
     };
 
@@ -92,13 +148,20 @@ fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Re
 
 // Compile the given Exercise as a test harness and display
 // the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, success_hints: bool) -> Result<bool, ()> {
+fn compile_and_test(
+    exercise: &Exercise,
+    run_mode: RunMode,
+    input: &RunInput,
+    verbose: bool,
+    success_hints: bool,
+    previous: Option<&Diagnostics>,
+) -> Result<bool, Error> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Testing {exercise}..."));
     progress_bar.enable_steady_tick(100);
 
-    let compilation = compile(exercise, &progress_bar)?;
-    let result = compilation.run();
+    let compilation = compile(exercise, &progress_bar, previous)?;
+    let result = compilation.run_with(input);
     progress_bar.finish_and_clear();
 
     match result {
@@ -112,112 +175,195 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
                 Ok(true)
             }
         }
-        Err(output) => {
+        Err(Error::Run(output)) => {
+            print_changes(previous, exercise, &output);
             warn!(
                 "Testing of {} failed! Please try again. Here's the output:",
                 exercise
             );
             println!("{}", output.stdout);
-            Err(())
+            Err(Error::Run(output))
+        }
+        Err(error) => {
+            warn!("{}", error);
+            Err(error)
         }
     }
 }
 
 // Compile the given Exercise and return an object with information
 // about the state of the compilation
-fn compile<'a, 'b>(
+fn compile<'a>(
     exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
-) -> Result<CompiledExercise<'a>, ()> {
+    progress_bar: &ProgressBar,
+    previous: Option<&Diagnostics>,
+) -> Result<CompiledExercise<'a>, Error> {
     let compilation_result = exercise.compile();
 
     match compilation_result {
         Ok(compilation) => Ok(compilation),
-        Err(output) => {
+        Err(Error::Compile(output)) => {
             progress_bar.finish_and_clear();
+            print_changes(previous, exercise, &output);
             warn!(
                 "Compiling of {} failed! Please try again. Here's the output:",
                 exercise
             );
             println!("{}", output.stderr);
-            Err(())
+            Err(Error::Compile(output))
+        }
+        Err(error) => {
+            progress_bar.finish_and_clear();
+            warn!("{}", error);
+            Err(error)
         }
     }
 }
 
 fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, success_hints: bool) -> bool {
     let context = match exercise.state() {
-        State::Done => return true,
-        State::Pending(context) => context,
+        Ok(State::Done) => return true,
+        Ok(State::Pending(context)) => context,
+        Err(error) => {
+            warn!("{}", error);
+            return false;
+        }
     };
-    match exercise.mode {
-        Mode::Compile => success!("Successfully ran {}!", exercise),
-        Mode::Test => success!("Successfully tested {}!", exercise),
-        Mode::Clippy => success!("Successfully compiled {}!", exercise),
-        Mode::BuildScript => success!("Successfully compiled {}!", exercise),
+    match exercise.runner().map(|runner| runner.check()) {
+        Ok(Check::Test) => success!("Successfully tested {}!", exercise),
+        Ok(Check::CompileOnly) => success!("Successfully compiled {}!", exercise),
+        _ => success!("Successfully ran {}!", exercise),
     }
 
-    let no_emoji = env::var("NO_EMOJI").is_ok();
+    let text = completion_text(exercise, prompt_output.as_deref(), success_hints, &context);
+    print!("{text}");
 
-    let clippy_success_msg = if no_emoji {
-        "The code is compiling, and Clippy is happy!"
-    } else {
-        "The code is compiling, and 📎 Clippy 📎 is happy!"
-    };
+    false
+}
 
-    let success_msg = match exercise.mode {
-        Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
-        Mode::Clippy => clippy_success_msg,
-        Mode::BuildScript => "Build script works!",
-    };
-    println!();
-    if no_emoji {
-        println!("~*~ {success_msg} ~*~")
+// What is shown for an exercise that works but still has its `I AM NOT DONE`
+// marker: the success message, the `output` of the exercise and, with
+// `success_hints`, its hints, followed by the `context` of the marker.
+// Watch mode prints it, and the full-screen interface renders it.
+pub fn completion_text(
+    exercise: &Exercise,
+    output: Option<&str>,
+    success_hints: bool,
+    context: &[ContextLine],
+) -> String {
+    let mut text = String::new();
+    let separator = separator();
+    let success_msg = success_message(exercise);
+    if env::var("NO_EMOJI").is_ok() {
+        let _ = writeln!(text, "\n~*~ {success_msg} ~*~\n");
     } else {
-        println!("🎉 🎉  {success_msg} 🎉 🎉")
+        let _ = writeln!(text, "\n🎉 🎉  {success_msg} 🎉 🎉\n");
     }
-    println!();
 
-    if let Some(output) = prompt_output {
-        println!("Output:");
-        println!("{}", separator());
-        println!("{output}");
-        println!("{}", separator());
-        println!();
+    if let Some(output) = output {
+        let _ = writeln!(text, "Output:\n{separator}\n{output}\n{separator}\n");
     }
     if success_hints {
-        println!("Hints:");
-        println!("{}", separator());
-        println!("{}", exercise.hint);
-        println!("{}", separator());
-        println!();
+        let hints = exercise.hint_levels().join("\n");
+        let _ = writeln!(text, "Hints:\n{separator}\n{hints}\n{separator}\n");
     }
 
-    println!("You can keep working on this exercise,");
-    println!(
-        "or jump into the next one by removing the {} comment:",
+    let _ = writeln!(text, "You can keep working on this exercise,");
+    let _ = writeln!(
+        text,
+        "or jump into the next one by removing the {} comment:\n",
         style("`I AM NOT DONE`").bold()
     );
-    println!();
     for context_line in context {
         let formatted_line = if context_line.important {
-            format!("{}", style(context_line.line).bold())
+            format!("{}", style(&context_line.line).bold())
         } else {
             context_line.line.to_string()
         };
 
-        println!(
+        let _ = writeln!(
+            text,
             "{:>2} {}  {}",
             style(context_line.number).blue().bold(),
             style("|").blue(),
             formatted_line
         );
     }
+    text
+}
 
-    false
+// The message celebrating that the exercise works, as its runner puts it
+fn success_message(exercise: &Exercise) -> String {
+    match exercise.runner() {
+        Ok(runner) => runner.success_message(),
+        Err(_) => "The exercise works!".to_string(),
+    }
 }
 
 fn separator() -> console::StyledObject<&'static str> {
     style("====================").bold()
 }
+
+fn print_table(rows: &[(&str, Counts)]) {
+    let width = rows
+        .iter()
+        .map(|(track, _)| track.len())
+        .chain([5])
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:<width$}  {:>6}  {:>6}  {:>8}",
+        "track", "passed", "failed", "not done"
+    );
+    let mut total = Counts::default();
+    for (track, counts) in rows {
+        println!(
+            "{:<width$}  {:>6}  {:>6}  {:>8}",
+            track, counts.passed, counts.failed, counts.not_done
+        );
+        total.passed += counts.passed;
+        total.failed += counts.failed;
+        total.not_done += counts.not_done;
+    }
+    println!(
+        "{:<width$}  {:>6}  {:>6}  {:>8}",
+        "total", total.passed, total.failed, total.not_done
+    );
+}
+
+// Verify every exercise instead of stopping at the first one that needs
+// work, and print how every track is doing. Returns whether all passed.
+pub fn verify_all(exercises: &[Exercise], jobs: usize) -> bool {
+    let bar = ProgressBar::new(exercises.len() as u64);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("Progress: [{bar:60.green/red}] {pos}/{len}")
+            .progress_chars("#>-"),
+    );
+    let verdicts = check_all(exercises, jobs, |exercise, verdict| {
+        let line = match verdict {
+            Verdict::Passed => format!("{} {exercise}", style("✓").green()),
+            Verdict::NotDone => format!("{} {exercise} is not done", style("…").yellow()),
+            Verdict::CompileError => format!("{} {exercise} doesn't compile", style("✗").red()),
+            Verdict::TestFailure => format!("{} {exercise} fails", style("✗").red()),
+        };
+        bar.println(line);
+        bar.inc(1);
+    });
+    bar.finish_and_clear();
+
+    println!();
+    print_table(&summary(exercises, &verdicts));
+    println!();
+    let passed = verdicts.iter().filter(|&&v| v == Verdict::Passed).count();
+    if passed == exercises.len() {
+        success!("All {} exercises passed!", passed);
+        true
+    } else {
+        warn!(
+            "{} exercise(s) need work, `rustlings verify` starts with the first one",
+            exercises.len() - passed
+        );
+        false
+    }
+}
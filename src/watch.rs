@@ -0,0 +1,635 @@
+use crate::diagnostics::Diagnostics;
+use crate::editor::{failure_location, open_editor, Location};
+use crate::run::run;
+use crate::verify::verify;
+use crate::{confirm, print_next_hint, print_time_remaining};
+use notify::DebouncedEvent;
+use notify::{PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use rustlings::exercise::{Exercise, ExerciseList, ExerciseOutput};
+use rustlings::pristine::reset;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub enum WatchStatus {
+    Finished,
+    Unfinished,
+}
+
+// A command typed into the watch shell that the watch loop has to act on
+enum ShellCommand {
+    Hint,
+    Output,
+    List,
+    Goto(String),
+    Skip,
+    Reset,
+    Run,
+    Quit,
+}
+
+// Everything the watch loop reacts to
+enum WatchEvent {
+    FileChanged(DebouncedEvent),
+    Shell(ShellCommand),
+}
+
+/* Clears the terminal with an ANSI escape code.
+Works in UNIX and newer Windows terminals. */
+fn clear_screen() {
+    println!("\x1Bc");
+}
+
+fn print_help() {
+    println!("Commands available to you in watch mode:");
+    println!("  hint        - reveals the current exercise's next hint");
+    println!("  output      - prints the last compiler or test output again");
+    println!("  list        - lists all exercises and their status");
+    println!("  goto <name> - jumps to the given exercise");
+    println!("  skip        - leaves the current exercise for later");
+    println!("  reset       - resets the current exercise to its original state");
+    println!("  run         - runs the current exercise again");
+    println!("  edit        - opens your editor where the current exercise needs work");
+    println!("  clear       - clears the screen");
+    println!("  quit        - quits watch mode");
+    println!("  !<cmd>      - executes a command, like `!rustc --explain E0381`");
+    println!("  help        - displays this help message");
+    println!();
+    println!("Watch mode automatically re-evaluates the current exercise");
+    println!("when you edit a file's contents.")
+}
+
+// `location` is where the current exercise needs work, kept up to date by the
+// watch loop. The editor is started from the shell thread, just like `!cmd`,
+// so that it doesn't have to compete with the shell for the terminal.
+fn spawn_watch_shell(tx: Sender<WatchEvent>, location: Arc<Mutex<Option<Location>>>) {
+    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
+    thread::spawn(move || {
+        let mut editor = match DefaultEditor::new() {
+            Ok(editor) => editor,
+            Err(e) => {
+                println!("error starting the watch shell: {e}");
+                return;
+            }
+        };
+        loop {
+            let input = match editor.readline("") {
+                Ok(input) => input,
+                Err(ReadlineError::Interrupted | ReadlineError::Eof) => {
+                    let _ = tx.send(WatchEvent::Shell(ShellCommand::Quit));
+                    return;
+                }
+                Err(error) => {
+                    println!("error reading command: {error}");
+                    continue;
+                }
+            };
+            let input = input.trim();
+            if input.is_empty() {
+                continue;
+            }
+            let _ = editor.add_history_entry(input);
+
+            let (name, argument) = match input.split_once(' ') {
+                Some((name, argument)) => (name, argument.trim()),
+                None => (input, ""),
+            };
+            let command = match (name, argument) {
+                ("hint", "") => ShellCommand::Hint,
+                ("output", "") => ShellCommand::Output,
+                ("list", "") => ShellCommand::List,
+                ("skip", "") => ShellCommand::Skip,
+                ("reset", "") => {
+                    let location = location.lock().unwrap().clone();
+                    if let Some(location) = location {
+                        let question =
+                            format!("Reset {} to its original state?", location.path.display());
+                        if !confirm(&question) {
+                            println!("Nothing was reset.");
+                            continue;
+                        }
+                    }
+                    ShellCommand::Reset
+                }
+                ("run", "") => ShellCommand::Run,
+                ("quit", "") => ShellCommand::Quit,
+                ("goto", "") => {
+                    println!("usage: goto <exercise name>");
+                    continue;
+                }
+                ("goto", exercise) => ShellCommand::Goto(exercise.to_string()),
+                ("edit", "") => {
+                    let location = location.lock().unwrap().clone();
+                    match location {
+                        Some(location) => {
+                            if let Err(e) = open_editor(&location) {
+                                println!("Could not open your editor: {e}");
+                            }
+                        }
+                        None => println!("There is no exercise to edit."),
+                    }
+                    continue;
+                }
+                ("clear", "") => {
+                    println!("\x1B[2J\x1B[1;1H");
+                    continue;
+                }
+                ("help", "") => {
+                    print_help();
+                    continue;
+                }
+                _ => {
+                    if let Some(cmd) = input.strip_prefix('!') {
+                        let parts: Vec<&str> = cmd.split_whitespace().collect();
+                        if parts.is_empty() {
+                            println!("no command provided");
+                        } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
+                            println!("failed to execute command `{}`: {}", cmd, e);
+                        }
+                    } else {
+                        println!("unknown command: {input}");
+                    }
+                    continue;
+                }
+            };
+            let quit = matches!(command, ShellCommand::Quit);
+            if tx.send(WatchEvent::Shell(command)).is_err() || quit {
+                return;
+            }
+        }
+    });
+}
+
+// The state of the watch loop between two verifications
+struct Session {
+    exercises: Vec<Exercise>,
+    verbose: bool,
+    success_hints: bool,
+    // The index of the exercise verification stopped at
+    current: Option<usize>,
+    // The output that was shown for the current exercise
+    output: Option<ExerciseOutput>,
+    // The problems found in that output, compared with the next run's
+    diagnostics: Option<Diagnostics>,
+    // Exercises the learner chose to leave for later
+    skipped: HashSet<String>,
+    // Where the current exercise needs work, shared with the shell
+    location: Arc<Mutex<Option<Location>>>,
+}
+
+impl Session {
+    fn current(&self) -> Option<&Exercise> {
+        self.current.map(|i| &self.exercises[i])
+    }
+
+    fn update_location(&self) {
+        let location = self
+            .current()
+            .map(|exercise| failure_location(exercise, self.output.as_ref()));
+        *self.location.lock().unwrap() = location;
+    }
+
+    // Verify the pending exercises, starting with `first`.
+    // Returns true once every exercise is done.
+    fn verify_from(&mut self, first: Option<usize>) -> bool {
+        let finished = self.verify_pending(first);
+        self.update_location();
+        finished
+    }
+
+    fn verify_pending(&mut self, first: Option<usize>) -> bool {
+        let pending_exercises = first.map(|i| &self.exercises[i]).into_iter().chain(
+            self.exercises.iter().enumerate().filter_map(|(i, e)| {
+                (!e.looks_done() && Some(i) != first && !self.skipped.contains(&e.name))
+                    .then_some(e)
+            }),
+        );
+        let num_done = self.exercises.iter().filter(|e| e.looks_done()).count();
+        clear_screen();
+        match verify(
+            pending_exercises,
+            (num_done, self.exercises.len()),
+            self.verbose,
+            self.success_hints,
+            self.diagnostics.as_ref(),
+        ) {
+            Ok(_) => {
+                self.current = None;
+                self.output = None;
+                self.diagnostics = None;
+                if self.skipped.is_empty() {
+                    return true;
+                }
+                let mut skipped: Vec<&str> = self.skipped.iter().map(String::as_str).collect();
+                skipped.sort_unstable();
+                println!(
+                    "All other exercises are done! You skipped: {}",
+                    skipped.join(", ")
+                );
+                println!("Type `goto <name>` to get back to one of them.");
+            }
+            Err(failure) => {
+                print_time_remaining(&self.exercises);
+                let current = self
+                    .exercises
+                    .iter()
+                    .position(|e| e.name == failure.exercise.name);
+                self.current = current;
+                self.diagnostics = failure
+                    .output
+                    .as_ref()
+                    .map(|output| Diagnostics::from_output(failure.exercise, output));
+                self.output = failure.output;
+            }
+        }
+        false
+    }
+
+    // Switch to a freshly loaded exercise list, keeping track of where the
+    // learner is as long as their exercise is still part of the course
+    fn reload(&mut self, exercises: Vec<Exercise>) {
+        let current = self.current().map(|e| e.name.clone());
+        self.exercises = exercises;
+        self.current = current.and_then(|name| self.exercises.iter().position(|e| e.name == name));
+        let exercises = &self.exercises;
+        self.skipped
+            .retain(|name| exercises.iter().any(|e| &e.name == name));
+    }
+
+    fn print_list(&self) {
+        for (i, exercise) in self.exercises.iter().enumerate() {
+            let marker = if self.current == Some(i) { ">" } else { " " };
+            let status = if self.skipped.contains(&exercise.name) {
+                "Skipped"
+            } else if exercise.looks_done() {
+                "Done"
+            } else {
+                "Pending"
+            };
+            println!("{marker} {:<17}\t{status}", exercise.name);
+        }
+    }
+
+    // Handle a shell command. Returns the status to quit with, if any.
+    fn handle(&mut self, command: ShellCommand) -> Option<WatchStatus> {
+        match command {
+            ShellCommand::Hint => {
+                if let Some(exercise) = self.current() {
+                    print_next_hint(exercise);
+                }
+            }
+            ShellCommand::Output => match &self.output {
+                Some(output) => {
+                    for text in [&output.stdout, &output.stderr] {
+                        if !text.trim().is_empty() {
+                            println!("{text}");
+                        }
+                    }
+                }
+                None => println!("There is no compiler or test output to show."),
+            },
+            ShellCommand::List => self.print_list(),
+            ShellCommand::Goto(name) => {
+                match self.exercises.iter().position(|e| e.name == name) {
+                    None => println!("No exercise found for '{name}'!"),
+                    Some(i) if self.exercises[i].looks_done() => {
+                        // Verifying a done exercise would move on right away
+                        self.skipped.remove(&name);
+                        clear_screen();
+                        let _ = run(&self.exercises[i], self.verbose);
+                        self.current = Some(i);
+                        self.output = None;
+                        self.update_location();
+                    }
+                    Some(i) => {
+                        self.skipped.remove(&name);
+                        if self.verify_from(Some(i)) {
+                            return Some(WatchStatus::Finished);
+                        }
+                    }
+                }
+            }
+            ShellCommand::Skip => match self.current() {
+                Some(exercise) => {
+                    self.skipped.insert(exercise.name.clone());
+                    if self.verify_from(None) {
+                        return Some(WatchStatus::Finished);
+                    }
+                }
+                None => println!("There is no exercise to skip."),
+            },
+            ShellCommand::Reset => match self.current() {
+                // The file watcher picks up the restored file
+                Some(exercise) => match reset(exercise) {
+                    Ok(()) => println!(
+                        "Your version was kept, `rustlings reset --undo {}` brings it back.",
+                        exercise.name
+                    ),
+                    Err(e) => warn!("Could not reset: {}", e),
+                },
+                None => println!("There is no exercise to reset."),
+            },
+            ShellCommand::Run => {
+                if self.verify_from(self.current) {
+                    return Some(WatchStatus::Finished);
+                }
+            }
+            ShellCommand::Quit => {
+                println!("Bye!");
+                return Some(WatchStatus::Unfinished);
+            }
+        }
+        None
+    }
+}
+
+// Where watch mode loads the course from, and how it notices changes
+pub struct WatchConfig<'a> {
+    pub info: &'a Path,
+    pub profile: Option<&'a str>,
+    // Names or glob patterns of the only exercises to watch, if any
+    pub focus: &'a [String],
+    // Poll the file system instead of relying on inotify and friends,
+    // which don't work on many network and container file systems
+    pub poll: bool,
+}
+
+impl WatchConfig<'_> {
+    pub fn load(&self) -> Result<ExerciseList, Box<dyn Error>> {
+        let mut list = ExerciseList::load_profile(self.info, self.profile)?;
+        if !self.focus.is_empty() {
+            list.focus(self.focus)?;
+        }
+        Ok(list)
+    }
+}
+
+// What a file system event means for the watch loop
+pub enum Change {
+    // info.toml or one of the files it includes
+    Config,
+    // A file belonging to the exercise with the given index
+    Exercise(usize),
+}
+
+// The files watch mode reacts to, by canonical path. Everything else under
+// ./exercises, like editor backups or build output, is ignored.
+pub struct WatchedFiles {
+    config: HashSet<PathBuf>,
+    exercises: HashMap<PathBuf, Vec<usize>>,
+    // Build scripts, manifests and config files are only considered changed
+    // when their contents are. rustlings writes the Cargo.toml of clippy and
+    // build script exercises itself while verifying them.
+    contents: HashMap<PathBuf, u64>,
+}
+
+impl WatchedFiles {
+    pub fn new(info: &Path, list: &ExerciseList) -> Self {
+        let config = iter::once(info)
+            .chain(list.include.iter().map(PathBuf::as_path))
+            .filter_map(|file| file.canonicalize().ok())
+            .collect();
+        let mut exercises: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+        for (i, exercise) in list.exercises.iter().enumerate() {
+            if let Ok(path) = exercise.path.canonicalize() {
+                exercises.entry(path).or_default().push(i);
+            }
+            // Like a crate's build.rs and Cargo.toml for the exercises built with cargo
+            let Ok(runner) = exercise.runner() else {
+                continue;
+            };
+            for file in runner.extra_files(exercise) {
+                let Some(dir) = file.parent().and_then(|d| d.canonicalize().ok()) else {
+                    continue;
+                };
+                if let Some(name) = file.file_name() {
+                    exercises.entry(dir.join(name)).or_default().push(i);
+                }
+            }
+        }
+        let mut files = WatchedFiles {
+            config,
+            exercises,
+            contents: HashMap::new(),
+        };
+        files.snapshot();
+        files
+    }
+
+    // Remember the current contents of the files that are compared by
+    // content, so that the events caused by verification itself are ignored
+    pub fn snapshot(&mut self) {
+        let paths: Vec<PathBuf> = self
+            .config
+            .iter()
+            .chain(self.exercises.keys())
+            .filter(|path| self.compares_contents(path))
+            .cloned()
+            .collect();
+        for path in paths {
+            match fs::read(&path) {
+                Ok(contents) => self.contents.insert(path, hash(&contents)),
+                Err(_) => self.contents.remove(&path),
+            };
+        }
+    }
+
+    fn compares_contents(&self, path: &Path) -> bool {
+        self.config.contains(path)
+            || path
+                .file_name()
+                .is_some_and(|name| name == "build.rs" || name == "Cargo.toml")
+    }
+
+    // Directories outside of ./exercises holding course files: the course
+    // description and the exercises of courses that keep them elsewhere
+    fn outside_dirs(&self) -> HashSet<PathBuf> {
+        let exercises = Path::new("exercises").canonicalize().ok();
+        self.config
+            .iter()
+            .chain(self.exercises.keys())
+            .filter(|file| !exercises.as_ref().is_some_and(|dir| file.starts_with(dir)))
+            .filter_map(|file| file.parent().map(Path::to_path_buf))
+            .collect()
+    }
+
+    // What the given event changed, if it's anything watch mode cares about.
+    // A file shared by several exercises maps to the first one not done yet.
+    pub fn classify(&mut self, event: &DebouncedEvent, exercises: &[Exercise]) -> Option<Change> {
+        let path = match event {
+            DebouncedEvent::Create(path)
+            | DebouncedEvent::Chmod(path)
+            | DebouncedEvent::Write(path)
+            | DebouncedEvent::Rename(_, path) => path.canonicalize().ok()?,
+            _ => return None,
+        };
+        if !self.config.contains(&path) && !self.exercises.contains_key(&path) {
+            return None;
+        }
+        if self.compares_contents(&path) {
+            let contents = hash(&fs::read(&path).ok()?);
+            if self.contents.insert(path.clone(), contents) == Some(contents) {
+                return None;
+            }
+        }
+        if self.config.contains(&path) {
+            return Some(Change::Config);
+        }
+        let owners = &self.exercises[&path];
+        owners
+            .iter()
+            .copied()
+            .find(|&i| exercises.get(i).is_some_and(|e| !e.looks_done()))
+            .or(owners.first().copied())
+            .map(Change::Exercise)
+    }
+}
+
+fn hash(contents: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+enum Backend {
+    Native(RecommendedWatcher),
+    Poll(PollWatcher),
+}
+
+// Watches ./exercises and the course description for changes
+pub struct FileWatcher {
+    backend: Backend,
+    watched: HashSet<PathBuf>,
+}
+
+impl FileWatcher {
+    pub fn new(tx: Sender<DebouncedEvent>, poll: bool) -> notify::Result<Self> {
+        let delay = Duration::from_secs(1);
+        let backend = if poll {
+            Backend::Poll(Watcher::new(tx, delay)?)
+        } else {
+            Backend::Native(Watcher::new(tx, delay)?)
+        };
+        let mut watcher = FileWatcher {
+            backend,
+            watched: HashSet::new(),
+        };
+        // Courses can keep their exercises elsewhere instead, which
+        // `watch_course` covers
+        let exercises = Path::new("./exercises");
+        if exercises.is_dir() {
+            watcher.watch(exercises, RecursiveMode::Recursive)?;
+        }
+        Ok(watcher)
+    }
+
+    fn watch(&mut self, path: &Path, mode: RecursiveMode) -> notify::Result<()> {
+        if !self.watched.insert(path.to_path_buf()) {
+            return Ok(());
+        }
+        match &mut self.backend {
+            Backend::Native(watcher) => watcher.watch(path, mode),
+            Backend::Poll(watcher) => watcher.watch(path, mode),
+        }
+    }
+
+    // Also watch the directories of the course files that live outside of
+    // ./exercises
+    pub fn watch_course(&mut self, files: &WatchedFiles) -> notify::Result<()> {
+        for dir in files.outside_dirs() {
+            self.watch(&dir, RecursiveMode::NonRecursive)?;
+        }
+        Ok(())
+    }
+}
+
+// Forward the events of the file watcher to the watch loop
+fn forward_file_events(fs_rx: Receiver<DebouncedEvent>, tx: Sender<WatchEvent>) {
+    thread::spawn(move || {
+        for event in fs_rx {
+            if tx.send(WatchEvent::FileChanged(event)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+pub fn watch(
+    config: &WatchConfig,
+    list: ExerciseList,
+    verbose: bool,
+    success_hints: bool,
+) -> notify::Result<WatchStatus> {
+    let mut files = WatchedFiles::new(config.info, &list);
+    let mut session = Session {
+        exercises: list.exercises,
+        verbose,
+        success_hints,
+        current: None,
+        output: None,
+        diagnostics: None,
+        skipped: HashSet::new(),
+        location: Arc::new(Mutex::new(None)),
+    };
+    // Watch before the first verification, so that saves made while it's
+    // compiling aren't missed
+    let (tx, rx) = channel();
+    let (fs_tx, fs_rx) = channel();
+    let mut watcher = FileWatcher::new(fs_tx, config.poll)?;
+    watcher.watch_course(&files)?;
+    forward_file_events(fs_rx, tx.clone());
+
+    if session.verify_from(None) {
+        return Ok(WatchStatus::Finished);
+    }
+    files.snapshot();
+    spawn_watch_shell(tx, Arc::clone(&session.location));
+    for event in rx {
+        let finished = match event {
+            WatchEvent::FileChanged(DebouncedEvent::Error(e, _)) => {
+                println!("watch error: {e:?}");
+                continue;
+            }
+            WatchEvent::FileChanged(event) => match files.classify(&event, &session.exercises) {
+                None => continue,
+                Some(Change::Exercise(i)) => {
+                    let name = session.exercises[i].name.clone();
+                    session.skipped.remove(&name);
+                    session.verify_from(Some(i))
+                }
+                Some(Change::Config) => match config.load() {
+                    Ok(list) => {
+                        files = WatchedFiles::new(config.info, &list);
+                        watcher.watch_course(&files)?;
+                        session.reload(list.exercises);
+                        session.verify_from(session.current)
+                    }
+                    Err(e) => {
+                        warn!("Could not reload the exercises: {}", e);
+                        continue;
+                    }
+                },
+            },
+            WatchEvent::Shell(command) => match session.handle(command) {
+                Some(status) => return Ok(status),
+                None => false,
+            },
+        };
+        if finished {
+            return Ok(WatchStatus::Finished);
+        }
+        files.snapshot();
+    }
+    Ok(WatchStatus::Unfinished)
+}
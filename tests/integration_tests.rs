@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use assert_cmd::prelude::*;
 use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
@@ -110,15 +112,597 @@ fn run_single_test_no_exercise() {
         .code(1);
 }
 
+// A fresh course in the temp dir with the given info.toml, for tests that
+// change the exercises
+fn temp_course(name: &str, info: &str) -> std::path::PathBuf {
+    let course = std::env::temp_dir().join(format!("rustlings-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&course);
+    std::fs::create_dir_all(&course).unwrap();
+    std::fs::write(course.join("info.toml"), info).unwrap();
+    course
+}
+
+// rustlings with the given arguments, run in a course from `temp_course`
+fn rustlings(course: &std::path::Path, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("rustlings").unwrap();
+    cmd.args(args).current_dir(course);
+    cmd
+}
+
+// Commit the files of a temp course, like the course author handing it out
+// or the learner saving their work
+fn commit_course(course: &std::path::Path) {
+    for args in [
+        &["init", "-q"][..],
+        &["add", "."],
+        &[
+            "-c",
+            "user.name=rustlings",
+            "-c",
+            "user.email=rustlings@localhost",
+            "commit",
+            "-q",
+            "-m",
+            "course",
+        ],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(course)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+}
+
 #[test]
 fn reset_single_exercise() {
-    Command::cargo_bin("rustlings")
+    let course = temp_course(
+        "reset",
+        "[[exercises]]\nname = \"reset_me\"\npath = \"reset_me.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    let exercise = course.join("reset_me.rs");
+    std::fs::write(&exercise, "// I AM NOT DONE\nfn main() {}\n").unwrap();
+    rustlings(&course, &["snapshot"]).assert().code(0);
+    commit_course(&course);
+    // Committing their work doesn't make it the learner's original
+    std::fs::write(&exercise, "fn main() { my_solution(); }\n").unwrap();
+    commit_course(&course);
+    rustlings(&course, &["reset", "reset_me"]).assert().code(1);
+    rustlings(&course, &["reset", "--yes", "reset_me"])
+        .assert()
+        .code(0);
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    assert_eq!(source, "// I AM NOT DONE\nfn main() {}\n");
+
+    rustlings(&course, &["reset", "--undo", "reset_me"])
+        .assert()
+        .code(0);
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    assert_eq!(source, "fn main() { my_solution(); }\n");
+    rustlings(&course, &["reset", "--undo", "reset_me"])
+        .assert()
+        .code(1);
+
+    std::fs::remove_dir_all(&course).unwrap();
+}
+
+#[test]
+fn reset_without_snapshot_restores_the_first_committed_version() {
+    let course = temp_course(
+        "reset-git",
+        "[[exercises]]\nname = \"reset_me\"\npath = \"reset_me.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    let exercise = course.join("reset_me.rs");
+    std::fs::write(&exercise, "// I AM NOT DONE\nfn main() {}\n").unwrap();
+    commit_course(&course);
+    std::fs::write(&exercise, "fn main() { my_solution(); }\n").unwrap();
+    commit_course(&course);
+    rustlings(&course, &["reset", "--yes", "reset_me"])
+        .assert()
+        .code(0);
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    assert_eq!(source, "// I AM NOT DONE\nfn main() {}\n");
+
+    std::fs::remove_dir_all(&course).unwrap();
+}
+
+#[test]
+fn snapshot_only_stores_unsolved_exercises() {
+    let course = temp_course(
+        "snapshot",
+        "[[exercises]]\nname = \"solved\"\npath = \"solved.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    let exercise = course.join("solved.rs");
+    std::fs::write(&exercise, "fn main() {}\n").unwrap();
+    rustlings(&course, &["snapshot"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "has no `I AM NOT DONE` marker",
+        ));
+    assert!(!course.join(".rustlings/pristine").exists());
+
+    std::fs::write(&exercise, "// I AM NOT DONE\nfn main() {}\n").unwrap();
+    rustlings(&course, &["snapshot"]).assert().code(0);
+    assert!(course.join(".rustlings/pristine/solved.rs").exists());
+    rustlings(&course, &["snapshot"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("pass --force"));
+    rustlings(&course, &["snapshot", "--force"])
+        .assert()
+        .code(0);
+
+    std::fs::remove_dir_all(&course).unwrap();
+}
+
+#[test]
+fn diff_against_original() {
+    let course = temp_course(
+        "diff",
+        "[[exercises]]\nname = \"diff_me\"\npath = \"diff_me.rs\"\nmode = \"test\"\nhint = \"\"\n",
+    );
+    let exercise = course.join("diff_me.rs");
+    let original = "// I AM NOT DONE\nfn answer() -> u32 {\n    0\n}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn works() {\n        assert_eq!(super::answer(), 42);\n    }\n}\n";
+    std::fs::write(&exercise, original).unwrap();
+    rustlings(&course, &["snapshot"]).assert().code(0);
+    commit_course(&course);
+    rustlings(&course, &["diff", "diff_me"])
+        .assert()
+        .code(0)
+        .stdout("diff_me.rs is unchanged.\n");
+    // Changes the learner committed are still changes
+    std::fs::write(&exercise, original.replace("    0\n", "    42\n")).unwrap();
+    commit_course(&course);
+    rustlings(&course, &["diff", "diff_me"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("-    0\n+    42\n"));
+    rustlings(&course, &["diff", "--tests-only", "diff_me"])
+        .assert()
+        .code(0)
+        .stdout("diff_me.rs is unchanged.\n");
+    rustlings(&course, &["diff", "--all"])
+        .assert()
+        .code(0)
+        .stdout(
+            predicates::str::contains("diff_me")
+                .and(predicates::str::contains("1 of 1 exercises changed")),
+        );
+    rustlings(&course, &["diff"]).assert().code(1);
+
+    std::fs::remove_dir_all(&course).unwrap();
+}
+
+#[test]
+fn solution_unlocks_after_passing() {
+    let course = temp_course(
+        "solution",
+        "[[exercises]]\nname = \"solve_me\"\npath = \"exercises/solve_me.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    std::fs::create_dir_all(course.join("exercises")).unwrap();
+    std::fs::create_dir_all(course.join("solutions")).unwrap();
+    let exercise = course.join("exercises/solve_me.rs");
+    std::fs::write(
+        &exercise,
+        "// I AM NOT DONE\nfn main() {\n    let x = 1\n}\n",
+    )
+    .unwrap();
+    let solution = "fn main() {\n    let _x = 1;\n}\n";
+    std::fs::write(course.join("solutions/solve_me.rs"), solution).unwrap();
+    rustlings(&course, &["solution", "solve_me"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "unlocks once the exercise passes",
+        ));
+    std::fs::write(
+        &exercise,
+        "fn main() {\n    let x = 1;\n    let _ = x;\n}\n",
+    )
+    .unwrap();
+    rustlings(&course, &["solution", "solve_me"])
+        .assert()
+        .code(0)
+        .stdout(solution);
+    rustlings(&course, &["solution", "--diff", "solve_me"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains(
+            "-    let _ = x;\n+    let _x = 1;\n",
+        ));
+
+    std::fs::remove_dir_all(&course).unwrap();
+}
+
+#[test]
+fn save_and_restore_work() {
+    let course = temp_course(
+        "archive",
+        "[[exercises]]\nname = \"keep_me\"\npath = \"keep_me.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    let exercise = course.join("keep_me.rs");
+    std::fs::write(&exercise, "// I AM NOT DONE\nfn main() {}\n").unwrap();
+    let archive = course.join("work.json");
+    rustlings(&course, &["snapshot"]).assert().code(0);
+    commit_course(&course);
+    // Work the learner committed is saved all the same
+    std::fs::write(&exercise, "fn main() { my_solution(); }\n").unwrap();
+    commit_course(&course);
+    rustlings(&course, &["save", "work.json"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains(
+            "1 changed exercise(s) of 1 were saved.",
+        ));
+    rustlings(&course, &["reset", "--yes", "keep_me"])
+        .assert()
+        .code(0);
+    rustlings(&course, &["restore", "work.json"])
+        .assert()
+        .code(0);
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    assert_eq!(source, "fn main() { my_solution(); }\n");
+
+    // An exercise that changed upstream is only restored with --force
+    std::fs::write(&exercise, "// I AM NOT DONE\nfn main() { todo!() }\n").unwrap();
+    rustlings(&course, &["snapshot", "--force"])
+        .assert()
+        .code(0);
+    std::fs::write(&exercise, "fn main() {}\n").unwrap();
+    rustlings(&course, &["restore", "work.json"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("keep_me.rs changed upstream"));
+    assert_eq!(
+        std::fs::read_to_string(&exercise).unwrap(),
+        "fn main() {}\n"
+    );
+    rustlings(&course, &["restore", "--force", "work.json"])
+        .assert()
+        .code(0);
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    assert_eq!(source, "fn main() { my_solution(); }\n");
+
+    let damaged = std::fs::read_to_string(&archive)
+        .unwrap()
+        .replace("my_solution", "your_solution");
+    std::fs::write(&archive, damaged).unwrap();
+    rustlings(&course, &["restore", "work.json"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("is damaged"));
+
+    std::fs::remove_dir_all(&course).unwrap();
+}
+
+#[test]
+fn submitted_bundle_is_graded_again() {
+    let course = temp_course(
+        "bundle",
+        "[[exercises]]\nname = \"passes\"\npath = \"exercises/passes.rs\"\nmode = \"compile\"\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"fails\"\npath = \"exercises/fails.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    std::fs::create_dir_all(course.join("exercises")).unwrap();
+    std::fs::write(course.join("exercises/passes.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(course.join("exercises/fails.rs"), "fn main() { x }\n").unwrap();
+    rustlings(&course, &["hint", "passes"]).assert().code(0);
+    rustlings(&course, &["submit", "--out", "bundle"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("1 of 2 exercises passed."));
+    assert!(course.join("bundle/sources/exercises/passes.rs").exists());
+    assert!(course.join("bundle/manifest.json").exists());
+    rustlings(&course, &["submit", "--out", "bundle"])
+        .assert()
+        .code(1);
+    rustlings(&course, &["grade-bundle", "bundle"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("The bundle matches"));
+    // Sources are kept relative to the course, however the info file is given
+    let info = course.join("info.toml").canonicalize().unwrap();
+    let info = info.to_str().unwrap();
+    rustlings(&course, &["--info", info, "grade-bundle", "bundle"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("The bundle matches"));
+    // The instructor's hint penalty counts, not the one the bundle was graded with
+    rustlings(&course, &["grade-bundle", "--hint-penalty", "0.5", "bundle"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "passes was submitted as passing with a score of 1, but passes with a score of 0.5 now",
+        ));
+
+    // Claiming a result that grading doesn't confirm is flagged
+    let results = course.join("bundle/check_result.json");
+    let claimed = std::fs::read_to_string(&results)
+        .unwrap()
+        .replace("\"result\": false", "\"result\": true");
+    std::fs::write(&results, claimed).unwrap();
+    rustlings(&course, &["grade-bundle", "bundle"])
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("check_result.json was changed after it was submitted").and(
+                predicates::str::contains("fails was submitted as passing with a score of 0"),
+            ),
+        );
+
+    // Files listed outside the bundle aren't read
+    let manifest = course.join("bundle/manifest.json");
+    let escaping = std::fs::read_to_string(&manifest)
         .unwrap()
-        .args(&["reset", "intro1"])
+        .replace("\"files\": {", "\"files\": {\n    \"../info.toml\": \"\",");
+    std::fs::write(&manifest, escaping).unwrap();
+    rustlings(&course, &["grade-bundle", "bundle"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("../info.toml points outside the bundle"));
+
+    std::fs::remove_dir_all(&course).unwrap();
+}
+
+#[test]
+fn ide_config_and_debug_build() {
+    let course = temp_course(
+        "ide",
+        "[[exercises]]\nname = \"step_me\"\npath = \"step_me.rs\"\nmode = \"test\"\nhint = \"\"\n",
+    );
+    std::fs::write(
+        course.join("step_me.rs"),
+        "#[test]\nfn it_works() {\n    assert_eq!(1 + 1, 2);\n}\n",
+    )
+    .unwrap();
+    rustlings(&course, &["ide", "vscode"]).assert().code(0);
+    let tasks = std::fs::read_to_string(course.join(".vscode/tasks.json")).unwrap();
+    assert!(tasks.contains("\"${file}\""));
+    let launch = std::fs::read_to_string(course.join(".vscode/launch.json")).unwrap();
+    assert!(launch.contains("target/rustlings/${fileBasenameNoExtension}"));
+    rustlings(&course, &["ide", "vscode"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("pass `--force` to replace it"));
+    rustlings(&course, &["ide", "--force", "vscode"])
+        .assert()
+        .code(0);
+
+    // Editors pass the absolute path of the open file
+    let path = course.join("step_me.rs");
+    rustlings(&course, &["build", path.to_str().unwrap()])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("target/rustlings/step_me"));
+    assert!(course.join("target/rustlings/step_me").exists());
+}
+
+#[test]
+fn run_passes_arguments_and_stdin() {
+    let course = temp_course(
+        "run-args",
+        "[[exercises]]\nname = \"echo_me\"\npath = \"echo_me.rs\"\nmode = \"compile\"\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"filter_me\"\npath = \"filter_me.rs\"\nmode = \"test\"\nhint = \"\"\n",
+    );
+    std::fs::write(
+        course.join("echo_me.rs"),
+        "fn main() {\n    let mut line = String::new();\n    std::io::stdin().read_line(&mut line).unwrap();\n    \
+         let args: Vec<String> = std::env::args().skip(1).collect();\n    println!(\"{} {}\", args.join(\",\"), line.trim());\n}\n",
+    )
+    .unwrap();
+    std::fs::write(course.join("input.txt"), "from stdin\n").unwrap();
+    std::fs::write(
+        course.join("filter_me.rs"),
+        "#[test]\nfn passes() {}\n\n#[test]\nfn fails() {\n    panic!();\n}\n",
+    )
+    .unwrap();
+    rustlings(
+        &course,
+        &["run", "echo_me", "--stdin", "input.txt", "--", "-a", "b"],
+    )
+    .assert()
+    .code(0)
+    .stdout(predicates::str::contains("-a,b from stdin"));
+    rustlings(&course, &["run", "filter_me"]).assert().code(1);
+    rustlings(&course, &["run", "filter_me", "--", "passes", "--exact"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("1 passed; 0 failed"));
+    rustlings(&course, &["run", "filter_me", "--stdin", "input.txt"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("don't read stdin"));
+}
+
+#[test]
+fn custom_mode_runs_the_command_from_info_toml() {
+    let course = temp_course(
+        "custom",
+        "[[exercises]]\nname = \"greet\"\npath = \"greet.rs\"\nmode = \"custom\"\n\
+         build = \"rustc {path} -o {binary}\"\ncommand = \"{binary} --name={name}\"\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"no_command\"\npath = \"no_command.rs\"\nmode = \"custom\"\nhint = \"\"\n",
+    );
+    std::fs::write(
+        course.join("greet.rs"),
+        "// I AM NOT DONE\nfn main() {\n    let args: Vec<String> = std::env::args().skip(1).collect();\n    \
+         println!(\"hello {}\", args.join(\" \"));\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        course.join("no_command.rs"),
+        "// I AM NOT DONE\nfn main() {}\n",
+    )
+    .unwrap();
+    rustlings(&course, &["run", "greet", "--", "world"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("hello --name=greet world"));
+    rustlings(&course, &["verify"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("The exercise works!"));
+    rustlings(&course, &["run", "no_command"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "has mode `custom` but no `command`",
+        ));
+    rustlings(&course, &["check-config"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "info.toml:12: exercise has mode `custom` but no `command`",
+        ));
+}
+
+#[test]
+fn doctest_mode_reports_failing_examples() {
+    let course = temp_course(
+        "doctest",
+        "[[exercises]]\nname = \"docs1\"\npath = \"docs1.rs\"\nmode = \"doctest\"\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"docs2\"\npath = \"docs2.rs\"\nmode = \"doctest\"\nmin_documented = 2\nhint = \"\"\n",
+    );
+    std::fs::write(
+        course.join("docs1.rs"),
+        "// I AM NOT DONE\n\n/// Adds two numbers\n///\n/// ```\n/// assert_eq!(docs1::add(1, 2), 3);\n/// ```\n\
+         pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n/// Doubles a number\n///\n/// ```\n\
+         /// assert_eq!(docs1::double(2), 5);\n/// ```\npub fn double(a: i32) -> i32 {\n    a * 2\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        course.join("docs2.rs"),
+        "// I AM NOT DONE\n\n/// Documented\npub fn documented() {}\n\npub fn undocumented() {}\n",
+    )
+    .unwrap();
+    rustlings(&course, &["run", "docs1"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("docs1.rs:14-16 (`double`)"));
+    rustlings(&course, &["run", "docs1", "--", "add"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("1 passed; 0 failed"));
+    rustlings(&course, &["run", "docs2"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "docs2.rs:6: `undocumented` has no doc comment",
+        ));
+}
+
+#[test]
+fn deny_warnings_fails_exercises_with_warnings() {
+    let course = temp_course(
+        "deny-warnings",
+        "deny_warnings = true\ninclude = [\"track/info.toml\"]\n\n\
+         [[exercises]]\nname = \"strict\"\npath = \"strict.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    let source = "// I AM NOT DONE\nfn main() {\n    let unused = 1;\n}\n";
+    std::fs::write(course.join("strict.rs"), source).unwrap();
+    std::fs::create_dir_all(course.join("track")).unwrap();
+    std::fs::write(
+        course.join("track/info.toml"),
+        "[[exercises]]\nname = \"inherited\"\npath = \"inherited.rs\"\nmode = \"test\"\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"lenient\"\npath = \"lenient.rs\"\nmode = \"compile\"\ndeny_warnings = false\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"scripted\"\npath = \"scripted/scripted.rs\"\nmode = \"buildscript\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(course.join("track/inherited.rs"), source).unwrap();
+    std::fs::write(course.join("track/lenient.rs"), source).unwrap();
+    std::fs::create_dir_all(course.join("track/scripted")).unwrap();
+    std::fs::write(course.join("track/scripted/scripted.rs"), source).unwrap();
+    std::fs::write(course.join("track/scripted/build.rs"), "fn main() {}\n").unwrap();
+    for name in ["strict", "inherited", "scripted"] {
+        rustlings(&course, &["run", name])
+            .env("NO_COLOR", "1")
+            .assert()
+            .code(1)
+            .stdout(
+                predicates::str::contains("error: unused variable: `unused`")
+                    .and(predicates::str::contains("let unused = 1;")),
+            );
+    }
+    rustlings(&course, &["run", "lenient"])
+        .env("NO_COLOR", "1")
         .assert()
         .code(0);
 }
 
+#[test]
+fn verify_keep_going_reports_every_track() {
+    let course = temp_course(
+        "keep-going",
+        "[[exercises]]\nname = \"pass_me\"\npath = \"basics/pass_me.rs\"\nmode = \"compile\"\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"broken\"\npath = \"basics/broken.rs\"\nmode = \"compile\"\nhint = \"\"\n\n\
+         [[exercises]]\nname = \"todo\"\npath = \"extras/todo.rs\"\nmode = \"test\"\nhint = \"\"\n",
+    );
+    std::fs::create_dir_all(course.join("basics")).unwrap();
+    std::fs::create_dir_all(course.join("extras")).unwrap();
+    std::fs::write(course.join("basics/pass_me.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(course.join("basics/broken.rs"), "fn main() { x }\n").unwrap();
+    std::fs::write(
+        course.join("extras/todo.rs"),
+        "// I AM NOT DONE\n#[test]\nfn works() {}\n",
+    )
+    .unwrap();
+
+    rustlings(&course, &["verify", "--keep-going", "--jobs", "2"])
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::is_match(r"(?m)^basics\s+1\s+1\s+0$")
+                .unwrap()
+                .and(predicates::str::is_match(r"(?m)^extras\s+0\s+0\s+1$").unwrap())
+                .and(predicates::str::contains("2 exercise(s) need work")),
+        );
+
+    std::fs::write(course.join("basics/broken.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(course.join("extras/todo.rs"), "#[test]\nfn works() {}\n").unwrap();
+    rustlings(&course, &["verify", "--keep-going"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("All 3 exercises passed!"));
+}
+
+#[test]
+fn course_can_be_checked_as_a_library() {
+    let list =
+        rustlings::ExerciseList::load(std::path::Path::new("tests/fixture/failure/info.toml"))
+            .unwrap();
+    let exercise = list
+        .exercises
+        .iter()
+        .find(|e| e.name == "compFailure")
+        .unwrap();
+    assert!(matches!(
+        exercise.compile(),
+        Err(rustlings::Error::Compile(_))
+    ));
+    assert!(matches!(
+        rustlings::evaluate(exercise),
+        rustlings::Outcome::CompileError(_)
+    ));
+
+    let missing = rustlings::ExerciseList::load(std::path::Path::new("tests/fixture/none.toml"));
+    assert!(matches!(missing, Err(rustlings::Error::Io { .. })));
+}
+
+#[test]
+fn doctor_reports_missing_course() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("doctor")
+        .current_dir("tests/")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("course: there is no info.toml here").and(
+                predicates::str::contains("fix: run rustlings from the rustlings directory"),
+            ),
+        );
+}
+
 #[test]
 fn reset_no_exercise() {
     Command::cargo_bin("rustlings")
@@ -142,6 +726,64 @@ fn get_hint_for_single_test() {
         .stdout("Hello!\n");
 }
 
+#[test]
+fn verify_opens_editor_at_compiler_error() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--open"])
+        .env("RUSTLINGS_EDITOR", "echo open {file}:{line}")
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("open compFailure.rs:3"));
+}
+
+#[test]
+fn watch_focus_needs_known_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "unknown*"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout("No exercise found for 'unknown*'!\n");
+}
+
+#[test]
+fn watch_focus_exits_once_selected_exercises_are_done() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "finished_*"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "All selected exercises are done!",
+        ));
+}
+
+#[test]
+fn hints_are_revealed_one_level_at_a_time() {
+    let _ = std::fs::remove_dir_all("tests/fixture/hints/.rustlings");
+    let hint = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["hint", "hinted"])
+            .current_dir("tests/fixture/hints")
+            .assert()
+            .code(0)
+    };
+    hint().stdout("Hint 1/3: Look at the types.\n");
+    hint().stdout(
+        predicates::str::contains("Hint 2/3").and(predicates::str::contains("Hint 3/3").not()),
+    );
+    hint().stdout(predicates::str::contains("That was the last hint"));
+    hint().stdout(predicates::str::contains("That was the last hint"));
+
+    let state = std::fs::read_to_string("tests/fixture/hints/.rustlings/hints.json").unwrap();
+    assert!(state.contains("\"hinted\": 3"));
+}
+
 #[test]
 fn all_exercises_require_confirmation() {
     for exercise in glob("exercises/**/*.rs").unwrap() {
@@ -264,3 +906,169 @@ fn run_rustlings_list_without_done() {
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn run_rustlings_list_with_alternate_info() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--info", "track/info.toml", "list", "--names"])
+        .current_dir("tests/fixture/include")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("track_exercise")
+                .and(predicates::str::contains("root_exercise").not()),
+        );
+}
+
+#[test]
+fn run_rustlings_list_with_profile() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--profile", "root_only", "list", "--names"])
+        .current_dir("tests/fixture/include")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("root_exercise")
+                .and(predicates::str::contains("track_exercise").not()),
+        );
+}
+
+#[test]
+fn run_rustlings_with_unknown_profile() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--profile", "nope", "list"])
+        .current_dir("tests/fixture/include")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn check_config_reports_every_problem() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-config")
+        .current_dir("tests/fixture/config")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("info.toml:1: cannot read missing.toml")
+                .and(predicates::str::contains(
+                    "info.toml:11: duplicate exercise name 'good_exercise'",
+                ))
+                .and(predicates::str::contains(
+                    "info.toml:12: no_such_file.rs does not exist",
+                ))
+                .and(predicates::str::contains(
+                    "info.toml:19: unknown mode 'interpret'",
+                ))
+                .and(predicates::str::contains(
+                    "info.toml:20: exercise has an empty hint",
+                ))
+                .and(predicates::str::contains(
+                    "info.toml:26: unknown edition '2020'",
+                ))
+                .and(predicates::str::contains(
+                    "info.toml:33: `min_documented` only applies to mode `doctest`",
+                ))
+                .and(predicates::str::contains(
+                    "info.toml:40: `difficulty` must be one of easy, medium, hard, not \"extreme\"",
+                ))
+                .and(predicates::str::contains(
+                    "info.toml:41: `estimated_minutes` must be a whole number of minutes, not -3",
+                ))
+                .and(predicates::str::contains(
+                    "info.toml:44: invalid pattern '[unclosed' in profile 'broken'",
+                )),
+        );
+}
+
+#[test]
+fn check_config_reports_profile_tags_no_exercise_has() {
+    let course = temp_course(
+        "profile-tags",
+        "[[exercises]]\nname = \"tagged\"\npath = \"tagged.rs\"\nmode = \"compile\"\ntags = [\"iterators\"]\nhint = \"a hint\"\n\n\
+         [profiles.practice]\ntags = [\"iterators\", \"iterator\"]\n",
+    );
+    std::fs::write(course.join("tagged.rs"), "// I AM NOT DONE\nfn main() {}\n").unwrap();
+
+    rustlings(&course, &["check-config"])
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains(
+                "info.toml:8: profile 'practice' refers to tag 'iterator', which no exercise has",
+            )
+            .and(predicates::str::contains("'iterators'").not()),
+        );
+}
+
+#[test]
+fn check_config_accepts_valid_config() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--info", "ok.toml", "check-config", "--solutions"])
+        .current_dir("tests/fixture/config")
+        .assert()
+        .success();
+}
+
+#[test]
+fn check_config_only_checks_solutions_a_course_ships() {
+    let course = temp_course(
+        "check-solutions",
+        "[[exercises]]\nname = \"first\"\npath = \"exercises/first.rs\"\nmode = \"compile\"\nhint = \"a hint\"\n\n\
+         [[exercises]]\nname = \"second\"\npath = \"exercises/second.rs\"\nmode = \"compile\"\nhint = \"a hint\"\n",
+    );
+    std::fs::create_dir_all(course.join("exercises")).unwrap();
+    for file in ["exercises/first.rs", "exercises/second.rs"] {
+        std::fs::write(course.join(file), "// I AM NOT DONE\nfn main() { x }\n").unwrap();
+    }
+    rustlings(&course, &["check-config", "--solutions"])
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("ships no reference solutions"));
+
+    std::fs::create_dir_all(course.join("solutions")).unwrap();
+    std::fs::write(course.join("solutions/first.rs"), "fn main() {}\n").unwrap();
+    rustlings(&course, &["check-config", "--solutions"])
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("'second' has no solution at solutions/second.rs")
+                .and(predicates::str::contains("'first'").not()),
+        );
+
+    std::fs::remove_dir_all(&course).unwrap();
+}
+
+#[test]
+fn check_config_looks_for_unlisted_exercises_next_to_the_info_file() {
+    let course = temp_course("unlisted", "");
+    let track = course.join("track");
+    std::fs::create_dir_all(track.join("exercises")).unwrap();
+    std::fs::create_dir_all(course.join("exercises")).unwrap();
+    std::fs::write(
+        track.join("info.toml"),
+        "[[exercises]]\nname = \"listed\"\npath = \"exercises/listed.rs\"\nmode = \"compile\"\nhint = \"a hint\"\n",
+    )
+    .unwrap();
+    for file in [
+        "track/exercises/listed.rs",
+        "track/exercises/unlisted.rs",
+        "exercises/elsewhere.rs",
+    ] {
+        std::fs::write(course.join(file), "// I AM NOT DONE\nfn main() {}\n").unwrap();
+    }
+
+    rustlings(&course, &["--info", "track/info.toml", "check-config"])
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("track/exercises/unlisted.rs is not listed")
+                .and(predicates::str::contains("elsewhere.rs").not())
+                .and(predicates::str::contains("listed.rs is not listed").count(1)),
+        );
+}